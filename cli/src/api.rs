@@ -0,0 +1,101 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+
+use aze_lib::auth::{
+    sign_request,
+    ACCOUNT_ID_HEADER,
+    NONCE_HEADER,
+    SIGNATURE_HEADER,
+    TIMESTAMP_HEADER,
+};
+use miden_objects::{ accounts::AccountId, crypto::dsa::rpo_falcon512::SecretKey };
+use reqwest::{ header::CONTENT_TYPE, Method, RequestBuilder };
+use serde::{ de::DeserializeOwned, Serialize };
+
+/// Client of the aze-server api.
+///
+/// Mutating calls and the ones revealing private state are signed with the key of the player
+/// set with `with_signer`, the others go out unsigned.
+pub struct AzeApi {
+    http: reqwest::Client,
+    base_url: String,
+    signer: Option<(AccountId, SecretKey)>,
+}
+
+impl AzeApi {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            signer: None,
+        }
+    }
+
+    pub fn with_signer(mut self, player_id: AccountId, key: SecretKey) -> Self {
+        self.signer = Some((player_id, key));
+        self
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        self.send(self.http.get(self.url(path))).await
+    }
+
+    pub async fn get_signed<T: DeserializeOwned>(&self, path: &str) -> Result<T, String> {
+        let request = self.signed(Method::GET, path, vec![])?;
+        self.send(request).await
+    }
+
+    pub async fn post<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B
+    ) -> Result<T, String> {
+        self.send(self.http.post(self.url(path)).json(body)).await
+    }
+
+    pub async fn post_signed<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B
+    ) -> Result<T, String> {
+        // the signature covers the raw body, so it is serialized once and sent as is
+        let body = serde_json::to_vec(body).map_err(|err| err.to_string())?;
+        let request = self.signed(Method::POST, path, body)?.header(CONTENT_TYPE, "application/json");
+        self.send(request).await
+    }
+
+    fn signed(&self, method: Method, path: &str, body: Vec<u8>) -> Result<RequestBuilder, String> {
+        let (player_id, key) = self.signer.as_ref().ok_or("no player account to sign the request with")?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is after the unix epoch")
+            .as_secs();
+        // the server accepts a nonce once per account, a random one never needs to be tracked here
+        let nonce: u64 = rand::random();
+        let signature = sign_request(key, method.as_str(), path, timestamp, nonce, &body);
+
+        Ok(
+            self.http
+                .request(method, self.url(path))
+                .header(ACCOUNT_ID_HEADER, u64::from(*player_id).to_string())
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(NONCE_HEADER, nonce.to_string())
+                .header(SIGNATURE_HEADER, signature)
+                .body(body)
+        )
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, String> {
+        let response = request.send().await.map_err(|err| err.to_string())?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let reason = response.text().await.unwrap_or_default();
+            return Err(format!("server answered {status}: {reason}"));
+        }
+        response.json().await.map_err(|err| err.to_string())
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+}
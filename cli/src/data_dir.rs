@@ -0,0 +1,54 @@
+use std::{ fs, io, path::{ Path, PathBuf } };
+
+use aze_types::accounts::TableSummary;
+
+const TABLES_FILE_NAME: &str = "tables.json";
+const PLAYER_FILE_NAME: &str = "player";
+
+/// Settings of this client kept in its data directory: the tables it hosts when it runs without
+/// a server, and the player account commands act for by default.
+pub struct DataDir {
+    dir: PathBuf,
+}
+
+impl DataDir {
+    pub fn open(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self { dir: dir.to_path_buf() })
+    }
+
+    pub fn keys_dir(&self) -> PathBuf {
+        self.dir.join("keys")
+    }
+
+    pub fn default_player(&self) -> Option<u64> {
+        fs::read_to_string(self.dir.join(PLAYER_FILE_NAME)).ok()?.trim().parse().ok()
+    }
+
+    pub fn set_default_player(&self, player_id: u64) -> io::Result<()> {
+        fs::write(self.dir.join(PLAYER_FILE_NAME), player_id.to_string())
+    }
+
+    pub fn tables(&self) -> io::Result<Vec<TableSummary>> {
+        match fs::read(self.dir.join(TABLES_FILE_NAME)) {
+            Ok(tables) => serde_json::from_slice(&tables).map_err(io::Error::from),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn table(&self, game_id: u64) -> io::Result<Option<TableSummary>> {
+        Ok(
+            self
+                .tables()?
+                .into_iter()
+                .find(|table| table.game_id == game_id)
+        )
+    }
+
+    pub fn add_table(&self, table: TableSummary) -> io::Result<()> {
+        let mut tables = self.tables()?;
+        tables.push(table);
+        fs::write(self.dir.join(TABLES_FILE_NAME), serde_json::to_vec_pretty(&tables)?)
+    }
+}
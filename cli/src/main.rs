@@ -0,0 +1,713 @@
+mod api;
+mod data_dir;
+
+use std::{ fs, path::{ Path, PathBuf }, time::{ SystemTime, UNIX_EPOCH } };
+
+use api::AzeApi;
+use aze_lib::accounts::{ aze_player_account_from_seed, create_basic_aze_player_account };
+use aze_lib::auth::sign_table_consent;
+use aze_lib::client::{ create_aze_client_with_config, AzeAccountTemplate, AzeClient, AzeGameMethods };
+use aze_lib::constants::{
+    CLIENT_CONFIG_FILE_NAME,
+    FIRST_PLAYER_INDEX,
+    MAX_PLAYERS,
+    MIN_PLAYERS,
+    PLAYER_INITIAL_BALANCE,
+    SMALL_BLIND_AMOUNT,
+};
+use aze_lib::executor::{
+    deserialize_unsigned_tx_request,
+    execute_tx_and_sync,
+    prove_tx_request,
+    record_relayed_transaction,
+};
+use aze_lib::keystore::AzeKeyStore;
+use aze_lib::notes::{ add_game_note_tag, game_note_tag };
+use aze_lib::rng::AzeRng;
+use aze_lib::storage::{ read_game_state, read_hole_cards, GameStorageSlotData };
+use aze_lib::table::{ deal_hole_cards, play_action_with_key };
+use aze_lib::transport::{
+    decode_note,
+    fetch_account_commitment,
+    import_private_note,
+    verify_game_snapshot,
+};
+use aze_lib::utils::load_config;
+use aze_types::accounts::{
+    AzeStorageMode,
+    GameCreationRequest,
+    GameCreationResponse,
+    GameSnapshot,
+    PlayerAccountCreationRequest,
+    PlayerAccountCreationResponse,
+    TableConsent,
+    TableSummary,
+    TablesResponse,
+};
+use aze_types::actions::{
+    Card,
+    GameActionRequest,
+    GameState,
+    PokerAction,
+    ProvenTransactionRequest,
+    UnsignedTransactionResponse,
+};
+use aze_types::notes::PrivateNotesResponse;
+use clap::{ Parser, Subcommand };
+use data_dir::DataDir;
+use miden_client::{
+    client::accounts::{ AccountStorageMode, AccountTemplate },
+    config::ClientConfig,
+    store::AuthInfo,
+};
+use miden_lib::AuthScheme;
+use miden_objects::{
+    accounts::{ Account, AccountId, AccountStorageType, AccountType },
+    assets::TokenSymbol,
+    crypto::{ dsa::rpo_falcon512::SecretKey, hash::rpo::RpoDigest },
+    utils::Serializable,
+    Word,
+};
+use rand::Rng;
+
+type CliResult<T> = Result<T, String>;
+
+/// Plays aze poker tables from the terminal.
+///
+/// With `--server` the tables live on an aze-server and actions are proven on this machine before
+/// being relayed by it, so the player key never leaves the data directory. Without it this client
+/// hosts the tables itself: it holds the game account and the keys of every player sitting at
+/// them, and proves all of their transactions against the node.
+#[derive(Parser)]
+#[command(name = "aze-cli", version)]
+struct Cli {
+    /// Url of the aze-server to play through
+    #[arg(long, env = "AZE_SERVER")]
+    server: Option<String>,
+    /// Miden client config, the store it points to keeps the accounts of this client
+    #[arg(long, default_value = CLIENT_CONFIG_FILE_NAME)]
+    config: PathBuf,
+    /// Directory of the player keys and of the tables hosted by this client
+    #[arg(long, env = "AZE_DATA_DIR", default_value = ".aze")]
+    data_dir: PathBuf,
+    /// Player account to act as, defaults to the last one created or imported
+    #[arg(long, env = "AZE_PLAYER")]
+    player: Option<u64>,
+    /// Passphrase of the player keys in the data directory, needed by every command a player signs
+    #[arg(long, env = "AZE_PASSPHRASE", hide_env_values = true, global = true)]
+    passphrase: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Player accounts, their keys are kept encrypted in the data directory
+    #[command(subcommand)]
+    Account(AccountCommand),
+    /// List the tables
+    Tables,
+    /// Open a table for the given players, seated in order
+    Open {
+        #[arg(long, value_delimiter = ',', required = true)]
+        players: Vec<u64>,
+        /// Faucet of the chips, a new one is created when a table is hosted without one
+        #[arg(long)]
+        faucet: Option<u64>,
+        #[arg(long, default_value_t = SMALL_BLIND_AMOUNT)]
+        small_blind: u8,
+        #[arg(long, default_value_t = PLAYER_INITIAL_BALANCE)]
+        buy_in: u8,
+        /// Consent of another player to a server table, as printed by `aze-cli consent`
+        #[arg(long = "consent")]
+        consents: Vec<String>,
+    },
+    /// Agree to sit at a server table with these terms, prints the consent to pass to `open`
+    Consent {
+        #[arg(long, value_delimiter = ',', required = true)]
+        players: Vec<u64>,
+        #[arg(long)]
+        faucet: u64,
+        #[arg(long, default_value_t = SMALL_BLIND_AMOUNT)]
+        small_blind: u8,
+        #[arg(long, default_value_t = PLAYER_INITIAL_BALANCE)]
+        buy_in: u8,
+    },
+    /// Pick up the hole cards dealt to the player at a table
+    Join {
+        game_id: u64,
+    },
+    /// Show the table and the player's hole cards
+    Show {
+        game_id: u64,
+    },
+    /// Bet `amount` chips
+    Bet {
+        game_id: u64,
+        amount: u8,
+    },
+    /// Raise the highest bet to `amount` chips
+    Raise {
+        game_id: u64,
+        amount: u8,
+    },
+    /// Match the highest bet
+    Call {
+        game_id: u64,
+    },
+    Check {
+        game_id: u64,
+    },
+    Fold {
+        game_id: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum AccountCommand {
+    /// Create a player account controlled by a new key
+    New,
+    /// Add an account from an exported key file and the seed it was created with
+    Import {
+        key_file: PathBuf,
+        /// Hex encoded account seed
+        #[arg(long)]
+        seed: String,
+        /// Passphrase the key file was exported with
+        #[arg(long, env = "AZE_KEY_FILE_PASSPHRASE", hide_env_values = true)]
+        key_file_passphrase: String,
+    },
+}
+
+struct Session {
+    client: AzeClient,
+    client_config: ClientConfig,
+    data_dir: DataDir,
+    server: Option<String>,
+    player: Option<u64>,
+    passphrase: Option<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli).await {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> CliResult<()> {
+    let client_config = load_config(&cli.config)?;
+    let mut session = Session {
+        client: create_aze_client_with_config(&client_config),
+        client_config,
+        data_dir: DataDir::open(&cli.data_dir).map_err(|err| err.to_string())?,
+        server: cli.server,
+        player: cli.player,
+        passphrase: cli.passphrase,
+    };
+
+    match cli.command {
+        Command::Account(AccountCommand::New) => session.new_account().await,
+        Command::Account(AccountCommand::Import { key_file, seed, key_file_passphrase }) =>
+            session.import_account(&key_file, &seed, &key_file_passphrase),
+        Command::Tables => session.list_tables().await,
+        Command::Open { players, faucet, small_blind, buy_in, consents } =>
+            session.open_table(players, faucet, small_blind, buy_in, consents).await,
+        Command::Consent { players, faucet, small_blind, buy_in } =>
+            session.consent(&players, faucet, small_blind, buy_in),
+        Command::Join { game_id } => session.join_table(game_id).await,
+        Command::Show { game_id } => session.show_table(game_id).await,
+        Command::Bet { game_id, amount } => session.play(game_id, PokerAction::Bet, Some(amount)).await,
+        Command::Raise { game_id, amount } =>
+            session.play(game_id, PokerAction::Raise, Some(amount)).await,
+        Command::Call { game_id } => session.play(game_id, PokerAction::Call, None).await,
+        Command::Check { game_id } => session.play(game_id, PokerAction::Check, None).await,
+        Command::Fold { game_id } => session.play(game_id, PokerAction::Fold, None).await,
+    }
+}
+
+impl Session {
+    async fn new_account(&mut self) -> CliResult<()> {
+        let keystore = self.keystore()?;
+        let key = SecretKey::with_rng(&mut rand::thread_rng());
+        let auth_scheme = AuthScheme::RpoFalcon512 { pub_key: key.public_key() };
+
+        let (account, seed) = match &self.server {
+            // the server has to know the account to check the requests it signs
+            Some(server) => {
+                let pub_key: Word = key.public_key().into();
+                let response: PlayerAccountCreationResponse = AzeApi::new(server).post(
+                    "/v1/player",
+                    &(PlayerAccountCreationRequest {
+                        pub_key: RpoDigest::from(pub_key).to_hex(),
+                    })
+                ).await?;
+
+                let seed = parse_word(&response.account_seed)?;
+                let account = aze_player_account_from_seed(seed, auth_scheme).map_err(|err|
+                    err.to_string()
+                )?;
+                if u64::from(account.id()) != response.account_id {
+                    return Err("the server created an account for another key".to_string());
+                }
+                (account, seed)
+            }
+            None =>
+                create_basic_aze_player_account(
+                    rand::thread_rng().gen(),
+                    auth_scheme,
+                    AccountType::RegularAccountImmutableCode,
+                    AccountStorageType::OffChain
+                ).map_err(|err| err.to_string())?,
+        };
+
+        keystore.insert_key(account.id(), &key).map_err(|err| err.to_string())?;
+        self.insert_player_account(&account, seed)?;
+        self.data_dir.set_default_player(account.id().into()).map_err(|err| err.to_string())?;
+
+        println!("player account {}", u64::from(account.id()));
+        println!("account seed {}", RpoDigest::from(seed).to_hex());
+        Ok(())
+    }
+
+    // the account is rebuilt the way it was created, which is all that can be recovered of an
+    // off-chain account from its seed
+    fn import_account(
+        &mut self,
+        key_file: &Path,
+        seed: &str,
+        key_file_passphrase: &str
+    ) -> CliResult<()> {
+        let keystore = self.keystore()?;
+        let key_file = fs::read(key_file).map_err(|err| err.to_string())?;
+        let account_id = keystore
+            .import_key(&key_file, key_file_passphrase)
+            .map_err(|err| err.to_string())?;
+        let key = keystore.get_key(account_id).map_err(|err| err.to_string())?;
+
+        let seed = parse_word(seed)?;
+        let auth_scheme = AuthScheme::RpoFalcon512 { pub_key: key.public_key() };
+        let account = aze_player_account_from_seed(seed, auth_scheme).map_err(|err| err.to_string())?;
+        if account.id() != account_id {
+            return Err("the seed does not belong to the account of the key file".to_string());
+        }
+
+        self.insert_player_account(&account, seed)?;
+        self.data_dir.set_default_player(account_id.into()).map_err(|err| err.to_string())?;
+
+        println!("player account {}", u64::from(account_id));
+        Ok(())
+    }
+
+    async fn list_tables(&mut self) -> CliResult<()> {
+        let tables = match &self.server {
+            Some(server) => AzeApi::new(server).get::<TablesResponse>("/v1/games").await?.tables,
+            None => self.data_dir.tables().map_err(|err| err.to_string())?,
+        };
+
+        for table in tables {
+            let players: Vec<String> = table.player_ids
+                .iter()
+                .map(|player_id| player_id.to_string())
+                .collect();
+            println!("{}  players {}  faucet {}", table.game_id, players.join(","), table.faucet_id);
+        }
+        Ok(())
+    }
+
+    async fn open_table(
+        &mut self,
+        players: Vec<u64>,
+        faucet: Option<u64>,
+        small_blind: u8,
+        buy_in: u8,
+        consents: Vec<String>
+    ) -> CliResult<()> {
+        if players.len() < (MIN_PLAYERS as usize) || players.len() > (MAX_PLAYERS as usize) {
+            return Err(format!("a table seats {MIN_PLAYERS} to {MAX_PLAYERS} players"));
+        }
+        // every player of a hosted table sits at this client, only a server needs their consent
+        if self.server.is_none() && !consents.is_empty() {
+            return Err("a table hosted by this client needs no consent".to_string());
+        }
+
+        if self.server.is_some() {
+            let faucet_id = faucet.ok_or("the server needs the faucet of the chips")?;
+            let consents = consents
+                .iter()
+                .map(|consent| serde_json::from_str::<TableConsent>(consent).map_err(|err| err.to_string()))
+                .collect::<CliResult<Vec<_>>>()?;
+            let request = GameCreationRequest {
+                player_ids: players,
+                small_blind,
+                buy_in,
+                faucet_id,
+                storage_mode: AzeStorageMode::Local,
+                consents,
+            };
+            let response: GameCreationResponse = self.api()?.post_signed("/v1/game", &request).await?;
+
+            println!("table {}", response.game_id);
+            return Ok(());
+        }
+
+        let player_ids = players
+            .iter()
+            .map(|player_id| parse_account_id(*player_id))
+            .collect::<CliResult<Vec<_>>>()?;
+        self.client.sync_state().await.map_err(|err| err.to_string())?;
+
+        let faucet_id = match faucet {
+            Some(faucet_id) => parse_account_id(faucet_id)?,
+            None => {
+                let (faucet_account, _) = self.client
+                    .new_account(AccountTemplate::FungibleFaucet {
+                        token_symbol: TokenSymbol::new("AZE").expect("valid token symbol"),
+                        decimals: 8,
+                        max_supply: 1_000_000_000,
+                        storage_mode: AccountStorageMode::Local,
+                    })
+                    .map_err(|err| err.to_string())?;
+                faucet_account.id()
+            }
+        };
+
+        let slot_data = GameStorageSlotData::new(
+            small_blind,
+            buy_in,
+            player_ids.len() as u8,
+            FIRST_PLAYER_INDEX,
+            small_blind,
+            buy_in
+        ).with_shuffle_seed(AzeRng.gen());
+        let (game_account, _) = self.client
+            .new_game_account(
+                AzeAccountTemplate::GameAccount {
+                    mutable_code: false,
+                    storage_mode: AccountStorageMode::Local,
+                },
+                Some(slot_data)
+            )
+            .map_err(|err| err.to_string())?;
+        let game_account_id = game_account.id();
+
+        // every player sits at this client, so the deal notes are consumed right away
+        let deal_notes = deal_hole_cards(
+            &mut self.client,
+            game_account_id,
+            &player_ids,
+            faucet_id,
+            buy_in as u64
+        ).await.map_err(|err| err.to_string())?;
+        for (player_id, deal_note) in player_ids.iter().zip(deal_notes) {
+            let player_auth = self.player_auth(*player_id)?;
+            let tx_request = self.client
+                .build_consume_notes_tx_request(*player_id, vec![deal_note.id()], player_auth)
+                .map_err(|err| err.to_string())?;
+            execute_tx_and_sync(&mut self.client, tx_request)
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+
+        self.data_dir
+            .add_table(TableSummary {
+                game_id: game_account_id.into(),
+                player_ids: players,
+                faucet_id: faucet_id.into(),
+            })
+            .map_err(|err| err.to_string())?;
+
+        println!("table {}", u64::from(game_account_id));
+        Ok(())
+    }
+
+    fn consent(&self, players: &[u64], faucet: u64, small_blind: u8, buy_in: u8) -> CliResult<()> {
+        let player_id = self.player_id()?;
+        if !players.contains(&player_id.into()) {
+            return Err("the player doesn't sit at this table".to_string());
+        }
+        let AuthInfo::RpoFalcon512(key) = self.player_auth(player_id)?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is after the unix epoch")
+            .as_secs();
+        let nonce: u64 = rand::random();
+        let consent = TableConsent {
+            player_id: player_id.into(),
+            timestamp,
+            nonce,
+            signature: sign_table_consent(&key, players, faucet, small_blind, buy_in, timestamp, nonce),
+        };
+
+        // only good for a few minutes, like any signed request
+        println!("{}", serde_json::to_string(&consent).map_err(|err| err.to_string())?);
+        Ok(())
+    }
+
+    async fn join_table(&mut self, game_id: u64) -> CliResult<()> {
+        if self.server.is_none() {
+            return Err("players of a table hosted by this client are seated when it is opened".to_string());
+        }
+        let game_account_id = parse_account_id(game_id)?;
+        let player_id = self.player_id()?;
+
+        // deal notes are private, so they come from the inbox rather than from the node
+        let inbox: PrivateNotesResponse = self
+            .api()?
+            .get_signed(&format!("/v1/player/{}/inbox", u64::from(player_id))).await?;
+        let game_tag = game_note_tag(game_account_id).map_err(|err| err.to_string())?;
+
+        let mut deal_note_ids = vec![];
+        for note in inbox.notes {
+            let note = decode_note(&note).ok_or("the inbox handed out a malformed note")?;
+            if note.metadata().tag() == game_tag {
+                deal_note_ids.push(note.id());
+            }
+            // notes stay in the inbox until consumed, those fetched by an earlier join are already known
+            if self.client.get_input_note(note.id()).is_err() {
+                import_private_note(&mut self.client, note).map_err(|err| err.to_string())?;
+            }
+        }
+        if deal_note_ids.is_empty() {
+            return Err(format!("no cards dealt to the player at table {game_id} yet"));
+        }
+
+        add_game_note_tag(&mut self.client, game_account_id).map_err(|err| err.to_string())?;
+        self.client.sync_state().await.map_err(|err| err.to_string())?;
+
+        let player_auth = self.player_auth(player_id)?;
+        let tx_request = self.client
+            .build_consume_notes_tx_request(player_id, deal_note_ids, player_auth)
+            .map_err(|err| err.to_string())?;
+        execute_tx_and_sync(&mut self.client, tx_request).await.map_err(|err| err.to_string())?;
+
+        self.print_hole_cards(player_id)
+    }
+
+    async fn show_table(&mut self, game_id: u64) -> CliResult<()> {
+        let game_account_id = parse_account_id(game_id)?;
+        self.client.sync_state().await.map_err(|err| err.to_string())?;
+
+        let game_state = if self.server.is_some() {
+            // the snapshot is only trusted once it matches the commitment on chain, the hidden cards are
+            // left out of it
+            let snapshot: GameSnapshot = self
+                .api()?
+                .get_signed(&format!("/v1/game/{game_id}/snapshot")).await?;
+            let commitment = fetch_account_commitment(&mut self.client, game_account_id).await.map_err(
+                |err| err.to_string()
+            )?;
+            let game_storage = verify_game_snapshot(&snapshot, commitment).ok_or(
+                "the table snapshot does not match its commitment on chain"
+            )?;
+            read_game_state(&game_storage)
+        } else {
+            let (game_account, _) = self.client
+                .get_account(game_account_id)
+                .map_err(|err| err.to_string())?;
+            read_game_state(game_account.storage())
+        };
+
+        print_table(&game_state);
+        match self.player_id() {
+            Ok(player_id) => self.print_hole_cards(player_id),
+            Err(_) => Ok(()),
+        }
+    }
+
+    async fn play(&mut self, game_id: u64, action: PokerAction, amount: Option<u8>) -> CliResult<()> {
+        let game_account_id = parse_account_id(game_id)?;
+        let player_id = self.player_id()?;
+
+        let game_state = match &self.server {
+            Some(_) => self.play_through_server(game_id, player_id, action, amount).await?,
+            None => {
+                let table = self.data_dir
+                    .table(game_id)
+                    .map_err(|err| err.to_string())?
+                    .ok_or(format!("no table {game_id} is hosted by this client"))?;
+                let seat = table.player_ids
+                    .iter()
+                    .position(|seated_id| *seated_id == u64::from(player_id))
+                    .ok_or("the player is not seated at the table")?;
+
+                let AuthInfo::RpoFalcon512(player_key) = self.player_auth(player_id)?;
+                play_action_with_key(
+                    &mut self.client,
+                    game_account_id,
+                    player_id,
+                    seat as u8,
+                    parse_account_id(table.faucet_id)?,
+                    action,
+                    amount,
+                    player_key
+                ).await.map_err(|err| err.to_string())?
+            }
+        };
+
+        print_table(&game_state);
+        Ok(())
+    }
+
+    // the server builds the action note, this client signs and proves the transaction sending it
+    async fn play_through_server(
+        &mut self,
+        game_id: u64,
+        player_id: AccountId,
+        action: PokerAction,
+        amount: Option<u8>
+    ) -> CliResult<GameState> {
+        let api = self.api()?;
+
+        let request = GameActionRequest { player_id: player_id.into(), action, amount };
+        let response: UnsignedTransactionResponse = api.post_signed(
+            &format!("/v1/game/{game_id}/actions/request"),
+            &request
+        ).await?;
+        let unsigned_request = hex
+            ::decode(&response.transaction_request)
+            .ok()
+            .and_then(|bytes| deserialize_unsigned_tx_request(&bytes).ok())
+            .ok_or("the server handed out a malformed transaction request")?;
+
+        self.client.sync_state().await.map_err(|err| err.to_string())?;
+        let player_auth = self.player_auth(player_id)?;
+        let tx_request = self.client
+            .sign_aze_tx_request(unsigned_request, player_auth)
+            .map_err(|err| err.to_string())?;
+        let (proven_transaction, transaction_result) = prove_tx_request(
+            &mut self.client,
+            tx_request
+        ).map_err(|err| err.to_string())?;
+
+        let request = ProvenTransactionRequest {
+            player_id: player_id.into(),
+            proven_transaction: hex::encode(proven_transaction.to_bytes()),
+        };
+        let game_state = api.post_signed(&format!("/v1/game/{game_id}/transactions"), &request).await?;
+
+        // the server submitted the transaction, so the store only learns about it from here
+        record_relayed_transaction(&self.client_config, transaction_result).map_err(|err|
+            err.to_string()
+        )?;
+        Ok(game_state)
+    }
+
+    fn player_id(&self) -> CliResult<AccountId> {
+        let player_id = self.player
+            .or_else(|| self.data_dir.default_player())
+            .ok_or("no player account, create one with `aze-cli account new`")?;
+        parse_account_id(player_id)
+    }
+
+    fn api(&self) -> CliResult<AzeApi> {
+        let server = self.server.as_ref().ok_or("no server to talk to")?;
+        let player_id = self.player_id()?;
+        let AuthInfo::RpoFalcon512(key) = self.player_auth(player_id)?;
+
+        Ok(AzeApi::new(server).with_signer(player_id, key))
+    }
+
+    fn keystore(&self) -> CliResult<AzeKeyStore> {
+        let passphrase = self.passphrase
+            .as_deref()
+            .ok_or("the player keys are encrypted, pass --passphrase or set AZE_PASSPHRASE")?;
+        AzeKeyStore::open(self.data_dir.keys_dir(), passphrase).map_err(|err| err.to_string())
+    }
+
+    // player keys are only ever read from the keystore, the miden store never holds them
+    fn player_auth(&self, player_id: AccountId) -> CliResult<AuthInfo> {
+        let key = self
+            .keystore()?
+            .get_key(player_id)
+            .map_err(|err| err.to_string())?;
+        Ok(AuthInfo::RpoFalcon512(key))
+    }
+
+    // the store wants a secret key with every account, so an unrelated one is kept there. It can
+    // never sign for the player, whose key stays encrypted in the keystore
+    fn insert_player_account(&mut self, account: &Account, seed: Word) -> CliResult<()> {
+        let placeholder_key = SecretKey::with_rng(&mut rand::thread_rng());
+        self.client
+            .insert_account(account, Some(seed), &AuthInfo::RpoFalcon512(placeholder_key))
+            .map_err(|err| err.to_string())
+    }
+
+    fn print_hole_cards(&self, player_id: AccountId) -> CliResult<()> {
+        let (player_account, _) = self.client.get_account(player_id).map_err(|err| err.to_string())?;
+
+        let hole_cards: Vec<String> = read_hole_cards(player_account.storage())
+            .iter()
+            .map(card_name)
+            .collect();
+
+        if !hole_cards.is_empty() {
+            println!("your cards  {}", hole_cards.join(" "));
+        }
+        Ok(())
+    }
+}
+
+fn print_table(game_state: &GameState) {
+    let phase = match game_state.current_phase {
+        0 => "pre-flop",
+        1 => "flop",
+        2 => "turn",
+        3 => "river",
+        _ => "showdown",
+    };
+    println!(
+        "{phase}  blinds {}/{}  highest bet {}",
+        game_state.small_blind,
+        game_state.big_blind,
+        game_state.highest_bet
+    );
+
+    for player in game_state.players.iter() {
+        let turn = if player.seat == game_state.current_turn { ">" } else { " " };
+        let raiser = if game_state.raiser == Some(player.seat) { "  raised" } else { "" };
+        let folded = if player.has_folded { "  folded" } else { "" };
+        println!(
+            "{turn} seat {}  bet {:>3}  balance {:>3}{raiser}{folded}",
+            player.seat,
+            player.bet,
+            player.balance
+        );
+    }
+
+    if !game_state.community_cards.is_empty() {
+        let community_cards: Vec<String> = game_state.community_cards.iter().map(card_name).collect();
+        println!("board  {}", community_cards.join(" "));
+    }
+}
+
+fn card_name(card: &Card) -> String {
+    let rank = match card.rank {
+        1 => "A".to_string(),
+        11 => "J".to_string(),
+        12 => "Q".to_string(),
+        13 => "K".to_string(),
+        rank => rank.to_string(),
+    };
+    let suit = match card.suit {
+        1 => '♣',
+        2 => '♦',
+        3 => '♥',
+        _ => '♠',
+    };
+    format!("{rank}{suit}")
+}
+
+fn parse_account_id(account_id: u64) -> CliResult<AccountId> {
+    AccountId::try_from(account_id).map_err(|err| err.to_string())
+}
+
+// words are exchanged with the server as hex encoded digests
+fn parse_word(word: &str) -> CliResult<Word> {
+    RpoDigest::try_from(word)
+        .map(Word::from)
+        .map_err(|err| err.to_string())
+}
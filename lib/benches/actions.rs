@@ -0,0 +1,228 @@
+use aze_lib::client::{ AzeAccountTemplate, AzeGameMethods };
+use aze_lib::constants::{
+    BUY_IN_AMOUNT,
+    FIRST_PLAYER_INDEX,
+    HIGHEST_BET,
+    MAX_PLAYERS,
+    PLAYER_INITIAL_BALANCE,
+    SMALL_BLIND_AMOUNT,
+};
+use aze_lib::executor::execute_tx_and_sync;
+use aze_lib::mock::{ create_mock_aze_client, MockAzeClient };
+use aze_lib::notes::{ compile_game_note_script, GameNoteKind };
+use aze_lib::storage::{ read_game_state, GameStorageSlotData };
+use aze_lib::table::{ action_transaction_template, deal_hole_cards };
+use aze_types::actions::PokerAction;
+use criterion::{ criterion_group, criterion_main, BenchmarkId, Criterion };
+use miden_client::client::{
+    accounts::{ AccountStorageMode, AccountTemplate },
+    transactions::transaction_request::{ TransactionRequest, TransactionTemplate },
+};
+use miden_lib::transaction::ToTransactionKernelInputs;
+use miden_objects::{
+    accounts::AccountId,
+    assets::TokenSymbol,
+    notes::NoteId,
+    transaction::ExecutedTransaction,
+};
+use miden_processor::{ execute, ExecutionOptions, MemAdviceProvider };
+use miden_tx::{ ProvingOptions, TransactionHost, TransactionProver };
+
+const PLAYER_COUNTS: [u8; 4] = [2, 4, 6, MAX_PLAYERS];
+
+// every transaction template, with the kind of note it sends and the action that note plays
+const TEMPLATES: [(&str, GameNoteKind, Option<PokerAction>); 6] = [
+    ("send_card", GameNoteKind::Deal, None),
+    ("play_bet", GameNoteKind::Bet, Some(PokerAction::Bet)),
+    ("play_raise", GameNoteKind::Raise, Some(PokerAction::Raise)),
+    ("play_call", GameNoteKind::Call, Some(PokerAction::Call)),
+    ("play_fold", GameNoteKind::Fold, Some(PokerAction::Fold)),
+    ("play_check", GameNoteKind::Check, Some(PokerAction::Check)),
+];
+
+// table on the in-memory chain along with, for every template, the transaction consuming the note
+// it sent. That is where the contracts run, so it is the one measured. None of them is submitted,
+// so each can be executed again and again against the same table.
+struct Table {
+    client: MockAzeClient,
+    consume_requests: Vec<(&'static str, TransactionRequest)>,
+}
+
+async fn open_table(player_count: u8) -> Table {
+    let mut client = create_mock_aze_client();
+
+    let slot_data = GameStorageSlotData::new(
+        SMALL_BLIND_AMOUNT,
+        BUY_IN_AMOUNT as u8,
+        player_count,
+        FIRST_PLAYER_INDEX,
+        HIGHEST_BET,
+        PLAYER_INITIAL_BALANCE
+    );
+    let (game_account, _) = client
+        .new_game_account(
+            AzeAccountTemplate::GameAccount {
+                mutable_code: false,
+                storage_mode: AccountStorageMode::Local,
+            },
+            Some(slot_data)
+        )
+        .unwrap();
+    let game_account_id = game_account.id();
+    let (faucet_account, _) = client
+        .new_account(AccountTemplate::FungibleFaucet {
+            token_symbol: TokenSymbol::new("MATIC").unwrap(),
+            decimals: 8,
+            max_supply: 1_000_000_000,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+    let faucet_account_id = faucet_account.id();
+
+    let mut player_account_ids = vec![];
+    for _ in 0..player_count {
+        let (player_account, _) = client
+            .new_game_account(
+                AzeAccountTemplate::PlayerAccount {
+                    mutable_code: false,
+                    storage_mode: AccountStorageMode::Local,
+                },
+                None
+            )
+            .unwrap();
+        player_account_ids.push(player_account.id());
+    }
+
+    // players need the chips of their deal to act, except for the last seat, whose deal note is
+    // left to measure the deal itself
+    let deal_notes = deal_hole_cards(
+        &mut client,
+        game_account_id,
+        &player_account_ids,
+        faucet_account_id,
+        BUY_IN_AMOUNT
+    ).await.unwrap();
+    let last_seat = (player_count - 1) as usize;
+    for seat in 0..last_seat {
+        let tx_request = consume_request(&mut client, player_account_ids[seat], deal_notes[seat].id());
+        execute_tx_and_sync(&mut client, tx_request).await.unwrap();
+    }
+
+    let mut consume_requests = vec![];
+    consume_requests.push((
+        TEMPLATES[0].0,
+        consume_request(&mut client, player_account_ids[last_seat], deal_notes[last_seat].id()),
+    ));
+
+    // the table never moves on, so each action is sent as the first one of the hand
+    let (game_account, _) = client.get_account(game_account_id).unwrap();
+    let game_state = read_game_state(game_account.storage());
+    let seat = game_state.current_turn;
+    for (name, _, action) in TEMPLATES {
+        let Some(action) = action else {
+            continue;
+        };
+        let amount = matches!(action, PokerAction::Bet | PokerAction::Raise).then_some(
+            SMALL_BLIND_AMOUNT * 2
+        );
+        let transaction_template = action_transaction_template(
+            &game_state,
+            seat,
+            player_account_ids[seat as usize],
+            game_account_id,
+            faucet_account_id,
+            action,
+            amount
+        ).unwrap();
+
+        let tx_request = client.build_aze_tx_request(transaction_template).unwrap();
+        execute_tx_and_sync(&mut client, tx_request.clone()).await.unwrap();
+
+        let note_id = tx_request.expected_output_notes()[0].id();
+        consume_requests.push((name, consume_request(&mut client, game_account_id, note_id)));
+    }
+
+    Table { client, consume_requests }
+}
+
+fn consume_request(
+    client: &mut MockAzeClient,
+    account_id: AccountId,
+    note_id: NoteId
+) -> TransactionRequest {
+    let tx_template = TransactionTemplate::ConsumeNotes(account_id, vec![note_id]);
+    client.build_transaction_request(tx_template).unwrap()
+}
+
+fn bench_note_script_compilation(c: &mut Criterion) {
+    // a script is compiled the same way whatever the table, so it is measured once per kind
+    let mut group = c.benchmark_group("note_script_compilation");
+    for (name, kind, _) in TEMPLATES {
+        group.bench_function(name, |b| b.iter(|| compile_game_note_script(kind)));
+    }
+    group.finish();
+}
+
+fn bench_transactions(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let mut tables: Vec<(u8, Table)> = PLAYER_COUNTS.iter()
+        .map(|player_count| (*player_count, runtime.block_on(open_table(*player_count))))
+        .collect();
+
+    let mut group = c.benchmark_group("execution");
+    for (player_count, Table { client, consume_requests }) in tables.iter_mut() {
+        for (name, tx_request) in consume_requests.iter() {
+            group.bench_with_input(BenchmarkId::new(*name, *player_count), tx_request, |b, tx_request| {
+                b.iter(|| client.new_transaction(tx_request.clone()).unwrap())
+            });
+        }
+    }
+    group.finish();
+
+    // proofs take seconds, the default hundred samples would keep a run going for hours
+    let prover = TransactionProver::new(ProvingOptions::default());
+    let mut group = c.benchmark_group("proving");
+    group.sample_size(10);
+    for (player_count, Table { client, consume_requests }) in tables.iter_mut() {
+        for (name, tx_request) in consume_requests.iter() {
+            let executed_transaction = client
+                .new_transaction(tx_request.clone())
+                .unwrap()
+                .executed_transaction()
+                .clone();
+
+            // criterion only keeps timings, so the cycle count is printed along
+            println!("cycles/{name}/{player_count}: {}", count_cycles(&executed_transaction));
+
+            group.bench_with_input(
+                BenchmarkId::new(*name, *player_count),
+                &executed_transaction,
+                |b, executed_transaction| {
+                    b.iter(|| prover.prove_transaction(executed_transaction.clone()).unwrap())
+                }
+            );
+        }
+    }
+    group.finish();
+}
+
+// runs the transaction kernel the way the prover does, but stops at the trace, whose main segment
+// has one row per cycle before it gets padded to the next power of two
+fn count_cycles(executed_transaction: &ExecutedTransaction) -> usize {
+    let (stack_inputs, advice_inputs) = executed_transaction.get_kernel_inputs();
+    let host = TransactionHost::new(
+        executed_transaction.initial_account().into(),
+        MemAdviceProvider::from(advice_inputs)
+    );
+    let trace = execute(
+        executed_transaction.program(),
+        stack_inputs,
+        host,
+        ExecutionOptions::default()
+    ).unwrap();
+
+    trace.trace_len_summary().main_trace_len()
+}
+
+criterion_group!(benches, bench_note_script_compilation, bench_transactions);
+criterion_main!(benches);
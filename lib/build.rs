@@ -9,6 +9,8 @@ use miden_assembly::{
     ast::{AstSerdeOptions, ProgramAst},
     LibraryNamespace, MaslLibrary, Version,
 };
+use miden_lib::transaction::TransactionKernel;
+use miden_objects::assembly::{AssemblyContext, LibraryPath, ModuleAst};
 
 // CONSTANTS
 // ================================================================================================
@@ -18,6 +20,12 @@ const ASM_DIR: &str = "asm";
 const ASM_MIDEN_DIR: &str = "miden";
 const ASM_NOTE_SCRIPTS_DIR: &str = "note_scripts";
 const ASM_KERNELS_DIR: &str = "kernels/transaction";
+const CONTRACTS_DIR: &str = "contracts";
+const GAME_NOTES_DIR: &str = "notes/game";
+const GAME_NOTES_ASSETS_DIR: &str = "game_notes";
+const CONTRACTS_CORE_DIR: &str = "core";
+const POKER_NAMESPACE: &str = "poker";
+const POKER_PROCEDURES_FILE: &str = "poker_procedures.rs";
 
 // PRE-PROCESSING
 // ================================================================================================
@@ -26,12 +34,16 @@ const ASM_KERNELS_DIR: &str = "kernels/transaction";
 /// - Compiles contents of asm/miden directory into a Miden library file (.masl) under
 ///   miden namespace.
 /// - Compiles contents of asm/scripts directory into individual .masb files.
+/// - Compiles the game note scripts in contracts/notes/game into individual .masb files.
+/// - Compiles contents of contracts/core directory into a Miden library file (.masl) under
+///   poker namespace, and writes the MAST roots of its procedures as Rust constants.
 #[cfg(not(feature = "docs-rs"))]
 fn main() -> io::Result<()> {
 
     println!("Building Masm");
     // re-build when the MASM code changes
     println!("cargo:rerun-if-changed=asm");
+    println!("cargo:rerun-if-changed=contracts");
 
     // Copies the MASM code to the build directory
     let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
@@ -56,9 +68,80 @@ fn main() -> io::Result<()> {
         &target_dir.join(ASM_NOTE_SCRIPTS_DIR),
     )?;
 
+    // game note scripts are read straight from the crate, they don't need the patched kernel
+    compile_note_scripts(
+        &Path::new(&crate_dir).join(CONTRACTS_DIR).join(GAME_NOTES_DIR),
+        &target_dir.join(GAME_NOTES_ASSETS_DIR),
+    )?;
+
+    // compile poker library
+    let contracts_core_dir = Path::new(&crate_dir).join(CONTRACTS_DIR).join(CONTRACTS_CORE_DIR);
+    compile_poker_lib(&contracts_core_dir, &target_dir)?;
+    write_poker_procedure_roots(&contracts_core_dir, &Path::new(&build_dir).join(POKER_PROCEDURES_FILE))?;
+
+    Ok(())
+}
+
+// COMPILE POKER LIB
+// ================================================================================================
+
+fn compile_poker_lib(source_dir: &Path, target_dir: &Path) -> io::Result<()> {
+    let ns = LibraryNamespace::try_from(POKER_NAMESPACE.to_string()).expect("invalid poker namespace");
+    let version = Version::try_from(env!("CARGO_PKG_VERSION")).expect("invalid cargo version");
+    let poker_lib = MaslLibrary::read_from_dir(source_dir, ns, true, version)?;
+
+    poker_lib.write_to_dir(target_dir)?;
+
     Ok(())
 }
 
+/// Writes a `{MODULE}_{PROCEDURE}` constant with the hex encoded MAST root of every procedure
+/// exported by the contracts, along with a `{MODULE}_PROCEDURES` list of them per module.
+///
+/// Roots are computed the way `AccountCode` compiles account code, so they match the procedures
+/// of accounts created from these contracts.
+fn write_poker_procedure_roots(source_dir: &Path, target_file: &Path) -> io::Result<()> {
+    let assembler = TransactionKernel::assembler();
+    let account_code_path = LibraryPath::new("context::account").expect("invalid account code path");
+
+    let mut roots = String::from("// generated by build.rs from the contracts in contracts/core\n\n");
+    roots.push_str(&format!(
+        "pub const POKER_LIBRARY_VERSION: &str = \"{}\";\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+
+    let mut masm_files = get_masm_files(source_dir)?;
+    masm_files.sort();
+    for masm_file_path in masm_files {
+        let module_name = masm_file_path.file_stem().unwrap().to_str().unwrap().to_uppercase();
+        let ast = ModuleAst::parse(&fs::read_to_string(&masm_file_path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+
+        // compiled roots come back in declaration order of the exports
+        let procedure_roots = assembler
+            .compile_module(&ast, Some(&account_code_path), &mut AssemblyContext::for_module(false))
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+        let procedure_names = ast
+            .procs()
+            .iter()
+            .filter(|procedure| procedure.is_export)
+            .map(|procedure| procedure.name.as_ref().to_string());
+
+        let mut procedures = Vec::new();
+        for (name, root) in procedure_names.zip(procedure_roots) {
+            let constant = format!("{module_name}_{}", name.to_uppercase());
+            roots.push_str(&format!("pub const {constant}: &str = \"{}\";\n", root.to_hex()));
+            procedures.push(format!("(\"{name}\", {constant})"));
+        }
+        roots.push_str(&format!(
+            "pub const {module_name}_PROCEDURES: &[(&str, &str)] = &[{}];\n",
+            procedures.join(", ")
+        ));
+    }
+
+    fs::write(target_file, roots)
+}
+
 // COMPILE MIDEN LIB
 // ================================================================================================
 
@@ -66,8 +149,9 @@ fn compile_miden_lib(source_dir: &Path, target_dir: &Path) -> io::Result<()> {
     let source_dir = source_dir.join(ASM_MIDEN_DIR);
 
     println!("cargo:warning=Compiling miden");
-    // if this build has the testing flag set, modify the code and reduce the cost of proof-of-work
-    match env::var("CARGO_FEATURE_TESTING") {
+    // if this build has the reduced-pow flag set, modify the code and reduce the cost of proof-of-work.
+    // kept apart from `testing`, which only adds the mock chain and must leave the assembled code as it is
+    match env::var("CARGO_FEATURE_REDUCED_POW") {
         Ok(ref s) if s == "1" => {
             let constants = source_dir.join("kernels/tx/constants.masm");
             let patched = source_dir.join("kernels/tx/constants.masm.patched");
@@ -9,7 +9,6 @@ use miden_objects::{
         StorageSlot,
         StorageSlotType,
     },
-    assembly::ModuleAst,
     assets::{ Asset, AssetVault },
     AccountError,
     Felt,
@@ -19,8 +18,10 @@ use miden_objects::{
 };
 
 use miden_lib::{ transaction::TransactionKernel, AuthScheme };
+use rand::{ rngs::StdRng, seq::SliceRandom, SeedableRng };
+use crate::contracts::poker_module;
 use crate::storage::GameStorageSlotData;
-use crate::constants::PLAYER_STATS_SLOTS;
+use crate::constants::{ GAME_STORAGE_VERSION, PLAYER_STATS_SLOTS };
 
 fn construct_game_constructor_storage(
     auth_scheme: AuthScheme,
@@ -47,24 +48,29 @@ fn construct_game_constructor_storage(
         slot: StorageSlot::new_value(storage_slot_0_data),
     };
 
-    for card_suit in 1..5 {
-        for card_number in 1..14 {
-            let slot_item: SlotItem = SlotItem {
-                index: slot_index,
-                slot: StorageSlot {
-                    slot_type: StorageSlotType::Value { value_arity: 0 },
-                    value: [
-                        Felt::from(card_suit as u8),
-                        Felt::from(card_number as u8),
-                        Felt::ZERO, // denotes is encrypted
-                        Felt::ZERO,
-                    ],
-                },
-            };
+    let mut deck: Vec<(u8, u8)> = (1..5u8)
+        .flat_map(|card_suit| (1..14u8).map(move |card_number| (card_suit, card_number)))
+        .collect();
+    if let Some(shuffle_seed) = slot_data.shuffle_seed() {
+        deck.shuffle(&mut StdRng::from_seed(shuffle_seed));
+    }
 
-            cards.push(slot_item);
-            slot_index += 1;
-        }
+    for (card_suit, card_number) in deck {
+        let slot_item: SlotItem = SlotItem {
+            index: slot_index,
+            slot: StorageSlot {
+                slot_type: StorageSlotType::Value { value_arity: 0 },
+                value: [
+                    Felt::from(card_suit),
+                    Felt::from(card_number),
+                    Felt::ZERO, // denotes is encrypted
+                    Felt::ZERO,
+                ],
+            },
+        };
+
+        cards.push(slot_item);
+        slot_index += 1;
     }
 
     let game_stats = vec![
@@ -115,6 +121,13 @@ fn construct_game_constructor_storage(
                 value: [Felt::ZERO, Felt::ZERO, Felt::ZERO, Felt::ZERO],
             },
         },
+        SlotItem {
+            index: slot_index + 6, // storing storage layout version
+            slot: StorageSlot {
+                slot_type: StorageSlotType::Value { value_arity: 0 },
+                value: [Felt::from(GAME_STORAGE_VERSION), Felt::ZERO, Felt::ZERO, Felt::ZERO],
+            },
+        },
         SlotItem {
             index: slot_index + 7, // storing curr turn pub key index
             slot: StorageSlot {
@@ -186,11 +199,12 @@ fn construct_game_constructor_storage(
 }
 
 // method to create a basic aze game account
-// it might also would take in cards but for now we are just initializing it with 52 hardcoded cards
+// the 52 cards are laid out in order, or shuffled with the seed of the slot data when it has one
 pub fn create_basic_aze_game_account(
     init_seed: [u8; 32],
     auth_scheme: AuthScheme,
     account_type: AccountType,
+    storage_type: miden_objects::accounts::AccountStorageType,
     slot_data: GameStorageSlotData
 ) -> Result<(Account, Word), AccountError> {
     if matches!(account_type, AccountType::FungibleFaucet | AccountType::NonFungibleFaucet) {
@@ -201,11 +215,7 @@ pub fn create_basic_aze_game_account(
         );
     }
 
-    let aze_game_account_code_src: &str = include_str!("../../contracts/core/game.masm");
-
-    let aze_game_account_code_ast = ModuleAst::parse(aze_game_account_code_src).map_err(|e|
-        AccountError::AccountCodeAssemblerError(e.into())
-    )?;
+    let aze_game_account_code_ast = poker_module("game");
     let account_assembler = TransactionKernel::assembler();
     let aze_game_account_code = AccountCode::new(
         aze_game_account_code_ast.clone(),
@@ -223,7 +233,7 @@ pub fn create_basic_aze_game_account(
     let account_seed = AccountId::get_account_seed(
         init_seed,
         account_type,
-        miden_objects::accounts::AccountStorageType::OnChain,
+        storage_type,
         aze_game_account_code.root(),
         aze_game_account_storage.root()
     )?;
@@ -248,7 +258,8 @@ pub fn create_basic_aze_game_account(
 pub fn create_basic_aze_player_account(
     init_seed: [u8; 32],
     auth_scheme: AuthScheme,
-    account_type: AccountType
+    account_type: AccountType,
+    storage_type: miden_objects::accounts::AccountStorageType
 ) -> Result<(Account, Word), AccountError> {
     if matches!(account_type, AccountType::FungibleFaucet | AccountType::NonFungibleFaucet) {
         return Err(
@@ -258,56 +269,74 @@ pub fn create_basic_aze_player_account(
         );
     }
 
-    let (_, storage_slot_0_data): (&str, Word) = match auth_scheme {
-        AuthScheme::RpoFalcon512 { pub_key } => ("basic::auth_tx_rpo_falcon512", pub_key.into()),
-    };
-
-    let aze_player_account_code_src: &str = include_str!("../../contracts/core/player.masm");
-
-    let aze_player_account_code_ast = ModuleAst::parse(aze_player_account_code_src).map_err(|e|
-        AccountError::AccountCodeAssemblerError(e.into())
-    )?;
-    let account_assembler = TransactionKernel::assembler();
-    let aze_player_account_code = AccountCode::new(
-        aze_player_account_code_ast.clone(),
-        &account_assembler
-    )?;
-    let aze_player_account_storage = AccountStorage::new(
-        vec![SlotItem {
-            index: 0,
-            slot: StorageSlot {
-                slot_type: StorageSlotType::Value { value_arity: 0 },
-                value: storage_slot_0_data,
-            },
-        }]
+    let (aze_player_account_code, aze_player_account_storage) = aze_player_code_and_storage(
+        auth_scheme
     )?;
-    let account_vault = AssetVault::new(&[]).expect("error on empty vault");
 
     let account_seed = AccountId::get_account_seed(
         init_seed,
         account_type,
-        miden_objects::accounts::AccountStorageType::OnChain,
+        storage_type,
         aze_player_account_code.root(),
         aze_player_account_storage.root()
     )?;
+    let account = aze_player_account_from_seed(account_seed, auth_scheme)?;
+    Ok((account, account_seed))
+}
+
+// rebuilds a fresh player account from the seed it was created with, e.g. one a server created
+// for a key the player holds, so it can be added to the player's own client
+pub fn aze_player_account_from_seed(
+    account_seed: Word,
+    auth_scheme: AuthScheme
+) -> Result<Account, AccountError> {
+    let (aze_player_account_code, aze_player_account_storage) = aze_player_code_and_storage(
+        auth_scheme
+    )?;
+    let account_vault = AssetVault::new(&[]).expect("error on empty vault");
+
     let account_id = AccountId::new(
         account_seed,
         aze_player_account_code.root(),
         aze_player_account_storage.root()
     )?;
-    Ok((
+    Ok(
         Account::new(
             account_id,
             account_vault,
             aze_player_account_storage,
             aze_player_account_code,
             ZERO
-        ),
-        account_seed,
-    ))
+        )
+    )
+}
+
+fn aze_player_code_and_storage(
+    auth_scheme: AuthScheme
+) -> Result<(AccountCode, AccountStorage), AccountError> {
+    let (_, storage_slot_0_data): (&str, Word) = match auth_scheme {
+        AuthScheme::RpoFalcon512 { pub_key } => ("basic::auth_tx_rpo_falcon512", pub_key.into()),
+    };
+
+    let aze_player_account_code_ast = poker_module("player");
+    let account_assembler = TransactionKernel::assembler();
+    let aze_player_account_code = AccountCode::new(
+        aze_player_account_code_ast.clone(),
+        &account_assembler
+    )?;
+    let aze_player_account_storage = AccountStorage::new(
+        vec![SlotItem {
+            index: 0,
+            slot: StorageSlot {
+                slot_type: StorageSlotType::Value { value_arity: 0 },
+                value: storage_slot_0_data,
+            },
+        }]
+    )?;
+    Ok((aze_player_account_code, aze_player_account_storage))
 }
 
-const fn account_id(account_type: AccountType, storage: AccountStorageType, rest: u64) -> u64 {
+pub(crate) const fn account_id(account_type: AccountType, storage: AccountStorageType, rest: u64) -> u64 {
     let mut id = 0;
 
     id ^= (storage as u64) << 62;
@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use miden_objects::{
+    accounts::AccountId,
+    crypto::{
+        dsa::rpo_falcon512::{ PublicKey, SecretKey, Signature },
+        hash::rpo::Rpo256,
+        utils::{ Deserializable, Serializable },
+    },
+    Word,
+};
+
+pub const ACCOUNT_ID_HEADER: &str = "x-aze-account-id";
+pub const TIMESTAMP_HEADER: &str = "x-aze-timestamp";
+pub const NONCE_HEADER: &str = "x-aze-nonce";
+pub const SIGNATURE_HEADER: &str = "x-aze-signature";
+
+// signed requests older than this are rejected, and their nonces only need to be kept that long
+pub const MAX_REQUEST_AGE_SECS: u64 = 300;
+
+// canonical digest of an api call: method, path with its query string, unix timestamp, nonce and raw
+// body, newline separated
+pub fn request_digest(method: &str, path: &str, timestamp: u64, nonce: u64, body: &[u8]) -> Word {
+    let mut message = Vec::with_capacity(method.len() + path.len() + body.len() + 64);
+    message.extend_from_slice(method.to_uppercase().as_bytes());
+    message.push(b'\n');
+    message.extend_from_slice(path.as_bytes());
+    message.push(b'\n');
+    message.extend_from_slice(timestamp.to_string().as_bytes());
+    message.push(b'\n');
+    message.extend_from_slice(nonce.to_string().as_bytes());
+    message.push(b'\n');
+    message.extend_from_slice(body);
+
+    Rpo256::hash(&message).into()
+}
+
+// returns the hex encoded signature to send in the signature header, `nonce` must not be reused
+// by the same account within `MAX_REQUEST_AGE_SECS`
+pub fn sign_request(
+    key_pair: &SecretKey,
+    method: &str,
+    path: &str,
+    timestamp: u64,
+    nonce: u64,
+    body: &[u8]
+) -> String {
+    let digest = request_digest(method, path, timestamp, nonce, body);
+    hex::encode(key_pair.sign(digest).to_bytes())
+}
+
+// digest a player signs to agree to sit at a table with these terms, the nonce and timestamp
+// make it single use just like a request. Prefixed so it can never pass as the digest of a request
+pub fn table_consent_digest(
+    player_ids: &[u64],
+    faucet_id: u64,
+    small_blind: u8,
+    buy_in: u8,
+    timestamp: u64,
+    nonce: u64
+) -> Word {
+    let player_ids: Vec<String> = player_ids
+        .iter()
+        .map(|player_id| player_id.to_string())
+        .collect();
+    let message = format!(
+        "TABLE CONSENT\n{}\n{faucet_id}\n{small_blind}\n{buy_in}\n{timestamp}\n{nonce}",
+        player_ids.join(",")
+    );
+
+    Rpo256::hash(message.as_bytes()).into()
+}
+
+// returns the hex encoded signature of a table consent, see `table_consent_digest`
+pub fn sign_table_consent(
+    key_pair: &SecretKey,
+    player_ids: &[u64],
+    faucet_id: u64,
+    small_blind: u8,
+    buy_in: u8,
+    timestamp: u64,
+    nonce: u64
+) -> String {
+    let digest = table_consent_digest(player_ids, faucet_id, small_blind, buy_in, timestamp, nonce);
+    hex::encode(key_pair.sign(digest).to_bytes())
+}
+
+// `pub_key` is the key commitment stored in slot 0 of the player account
+pub fn verify_request_signature(pub_key: Word, digest: Word, signature_hex: &str) -> bool {
+    let Ok(signature_bytes) = hex::decode(signature_hex) else {
+        return false;
+    };
+    let Ok(signature) = Signature::read_from_bytes(&signature_bytes) else {
+        return false;
+    };
+
+    PublicKey::new(pub_key).verify(digest, &signature)
+}
+
+// whether a request signed at `timestamp` can still be accepted at `now`, both in unix seconds
+pub fn is_request_fresh(timestamp: u64, now: u64) -> bool {
+    now.abs_diff(timestamp) <= MAX_REQUEST_AGE_SECS
+}
+
+/// Nonces of the signed requests accepted so far, so each signature is only ever accepted once.
+///
+/// A nonce is kept as long as the timestamp of its request is fresh. Once that expires the
+/// request is rejected on its timestamp anyway, so the nonce is forgotten.
+#[derive(Default)]
+pub struct NonceRegistry {
+    used: Mutex<BTreeMap<(AccountId, u64), u64>>,
+}
+
+impl NonceRegistry {
+    /// Records the nonce of a request signed by `account_id` at `timestamp`, returns false if that
+    /// account already used it.
+    pub fn register(&self, account_id: AccountId, nonce: u64, timestamp: u64, now: u64) -> bool {
+        let mut used = self.used.lock().unwrap();
+        used.retain(|_, signed_at| is_request_fresh(*signed_at, now));
+        used.insert((account_id, nonce), timestamp).is_none()
+    }
+}
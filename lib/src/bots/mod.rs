@@ -0,0 +1,298 @@
+use aze_types::actions::{ Card, GameState, PokerAction };
+use miden_client::{ client::{ rpc::NodeRpcClient, Client }, store::Store };
+use miden_objects::{ accounts::AccountId, crypto::rand::FeltRng };
+use rand::{ rngs::StdRng, Rng, SeedableRng };
+
+use crate::engine::BettingRound;
+use crate::storage::{ read_game_state, read_hole_cards };
+use crate::table::{ play_action, TableError };
+
+// phase the table moves to once the river betting is over, nothing is played past it
+const SHOWDOWN_PHASE: u8 = 4;
+const ACE_HIGH: u8 = 14;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BotAction {
+    pub action: PokerAction,
+    // only set for bets and raises
+    pub amount: Option<u8>,
+}
+
+impl BotAction {
+    pub fn bet(amount: u8) -> Self {
+        Self { action: PokerAction::Bet, amount: Some(amount) }
+    }
+
+    pub fn raise(amount: u8) -> Self {
+        Self { action: PokerAction::Raise, amount: Some(amount) }
+    }
+
+    pub fn call() -> Self {
+        Self { action: PokerAction::Call, amount: None }
+    }
+
+    pub fn check() -> Self {
+        Self { action: PokerAction::Check, amount: None }
+    }
+
+    pub fn fold() -> Self {
+        Self { action: PokerAction::Fold, amount: None }
+    }
+}
+
+/// Decides the actions of an automated player.
+///
+/// A strategy is only asked for the seat holding the turn. It is handed the table decoded from
+/// the game account and its own hole cards, which is all a player at the table gets to see.
+pub trait Strategy {
+    fn decide(&mut self, game_state: &GameState, seat: u8, hole_cards: &[Card]) -> BotAction;
+}
+
+/// Matches whatever is bet, and checks when there is nothing to match.
+pub struct AlwaysCall;
+
+impl Strategy for AlwaysCall {
+    fn decide(&mut self, game_state: &GameState, seat: u8, _hole_cards: &[Card]) -> BotAction {
+        passive_action(game_state, seat)
+    }
+}
+
+/// Picks any action the player can afford, seeded so a table can be replayed.
+pub struct RandomStrategy {
+    rng: StdRng,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn decide(&mut self, game_state: &GameState, seat: u8, _hole_cards: &[Card]) -> BotAction {
+        let balance = game_state.players[seat as usize].balance;
+
+        // folding is kept rarer than the rest so tables get past the flop now and then
+        match self.rng.gen_range(0..4) {
+            0 => BotAction::fold(),
+            1 if balance > game_state.highest_bet =>
+                BotAction::raise(self.rng.gen_range(game_state.highest_bet + 1..=balance)),
+            _ => passive_action(game_state, seat),
+        }
+    }
+}
+
+/// Plays few hands and plays them hard: raises strong hands, calls decent ones and gives up on
+/// the rest unless checking is free. Hands are rated with `hand_strength`.
+pub struct TightAggressive {
+    raise_threshold: f64,
+    call_threshold: f64,
+}
+
+impl TightAggressive {
+    pub fn new(raise_threshold: f64, call_threshold: f64) -> Self {
+        Self { raise_threshold, call_threshold }
+    }
+}
+
+impl Default for TightAggressive {
+    fn default() -> Self {
+        Self::new(0.7, 0.45)
+    }
+}
+
+impl Strategy for TightAggressive {
+    fn decide(&mut self, game_state: &GameState, seat: u8, hole_cards: &[Card]) -> BotAction {
+        let strength = hand_strength(hole_cards, &game_state.community_cards);
+        let player = &game_state.players[seat as usize];
+
+        // a raise is the whole new bet, so it is capped by the balance rather than the difference
+        let raise_to = game_state.highest_bet
+            .saturating_add(game_state.big_blind)
+            .min(player.balance);
+        let can_raise = raise_to > game_state.highest_bet && game_state.raiser != Some(seat);
+
+        if strength >= self.raise_threshold && can_raise {
+            BotAction::raise(raise_to)
+        } else if strength >= self.call_threshold {
+            passive_action(game_state, seat)
+        } else if amount_to_call(game_state, seat) == 0 {
+            BotAction::check()
+        } else {
+            BotAction::fold()
+        }
+    }
+}
+
+/// Rates the hole cards against the community cards revealed so far, from 0 to 1.
+///
+/// Before the flop only the hole cards count: pairs first, then high, suited and connected
+/// cards. Afterwards the best made hand decides, with the highest rank it is made of breaking
+/// ties within a category, so anything from two pair up rates as strong.
+pub fn hand_strength(hole_cards: &[Card], community_cards: &[Card]) -> f64 {
+    if hole_cards.len() < 2 {
+        return 0.0;
+    }
+
+    if community_cards.is_empty() {
+        let high = rank_value(&hole_cards[0]).max(rank_value(&hole_cards[1])) as f64;
+        let low = rank_value(&hole_cards[0]).min(rank_value(&hole_cards[1])) as f64;
+        let ace = ACE_HIGH as f64;
+
+        if high == low {
+            return 0.5 + (high / ace) * 0.5;
+        }
+        let mut strength = ((high + low) / (2.0 * ace)) * 0.6;
+        if hole_cards[0].suit == hole_cards[1].suit {
+            strength += 0.05;
+        }
+        if high - low == 1.0 {
+            strength += 0.05;
+        }
+        return strength;
+    }
+
+    let cards: Vec<Card> = hole_cards.iter().chain(community_cards.iter()).copied().collect();
+    let (category, top_rank) = made_hand(&cards);
+    ((category as f64 + (top_rank as f64) / (ACE_HIGH as f64)) / 3.0).min(1.0)
+}
+
+/// Automated player sitting at a table.
+pub struct Bot {
+    pub account_id: AccountId,
+    pub strategy: Box<dyn Strategy>,
+}
+
+/// Plays the hand at a table hosted by `client` until the showdown, or until a single player is
+/// left in it, and returns the table as it ends.
+///
+/// `bots` sit in seat order, one per seat. Whenever a seat holds the turn its bot is handed the
+/// table and its hole cards, and the action it picks is played with `play_action`. An action the
+/// betting rules reject is replaced by a fold, so a faulty strategy can't stall the table. At
+/// most `max_actions` are played, the table is returned as it stands after that.
+pub async fn run_bots<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    game_account_id: AccountId,
+    faucet_account_id: AccountId,
+    bots: &mut [Bot],
+    max_actions: usize
+) -> Result<GameState, TableError> {
+    for _ in 0..max_actions {
+        let (game_account, _) = client.get_account(game_account_id)?;
+        let game_state = read_game_state(game_account.storage());
+        if is_hand_over(&game_state) {
+            return Ok(game_state);
+        }
+
+        let seat = game_state.current_turn;
+        let bot = bots.get_mut(seat as usize).expect("a bot sits in every seat");
+        let (bot_account, _) = client.get_account(bot.account_id)?;
+        let hole_cards = read_hole_cards(bot_account.storage());
+
+        let mut decision = bot.strategy.decide(&game_state, seat, &hole_cards);
+        let is_legal = BettingRound::from(&game_state)
+            .apply(decision.action, decision.amount.unwrap_or(0))
+            .is_ok();
+        if !is_legal {
+            decision = BotAction::fold();
+        }
+
+        play_action(
+            client,
+            game_account_id,
+            bot.account_id,
+            seat,
+            faucet_account_id,
+            decision.action,
+            decision.amount
+        ).await?;
+    }
+
+    let (game_account, _) = client.get_account(game_account_id)?;
+    Ok(read_game_state(game_account.storage()))
+}
+
+pub fn is_hand_over(game_state: &GameState) -> bool {
+    let active_players = game_state.players
+        .iter()
+        .filter(|player| !player.has_folded)
+        .count();
+    game_state.current_phase >= SHOWDOWN_PHASE || active_players <= 1
+}
+
+// chips the seat has to put in to match the highest bet
+fn amount_to_call(game_state: &GameState, seat: u8) -> u8 {
+    game_state.highest_bet.saturating_sub(game_state.players[seat as usize].bet)
+}
+
+// calls when behind and checks otherwise, folding only when the call can't be afforded
+fn passive_action(game_state: &GameState, seat: u8) -> BotAction {
+    let to_call = amount_to_call(game_state, seat);
+    if to_call == 0 {
+        BotAction::check()
+    } else if to_call <= game_state.players[seat as usize].balance {
+        BotAction::call()
+    } else {
+        BotAction::fold()
+    }
+}
+
+// aces are dealt as rank 1 and rate above kings
+fn rank_value(card: &Card) -> u8 {
+    if card.rank == 1 { ACE_HIGH } else { card.rank }
+}
+
+// category of the best hand in `cards`, from 0 for high card to 8 for a straight flush, along
+// with the highest rank it is made of
+fn made_hand(cards: &[Card]) -> (u8, u8) {
+    let mut rank_counts = [0u8; ACE_HIGH as usize + 1];
+    for card in cards {
+        rank_counts[rank_value(card) as usize] += 1;
+    }
+    let ranks_with = |count: u8| -> Vec<u8> {
+        (2..=ACE_HIGH).rev().filter(|rank| rank_counts[*rank as usize] >= count).collect()
+    };
+
+    let flush_cards: Option<Vec<Card>> = (1..=4u8)
+        .map(|suit| cards.iter().filter(|card| card.suit == suit).copied().collect::<Vec<_>>())
+        .find(|suited| suited.len() >= 5);
+
+    if let Some(top_rank) = flush_cards.as_deref().and_then(straight_top) {
+        return (8, top_rank);
+    }
+    if let Some(&rank) = ranks_with(4).first() {
+        return (7, rank);
+    }
+    let trips = ranks_with(3);
+    let pairs = ranks_with(2);
+    if let Some(&rank) = trips.first() {
+        if pairs.iter().any(|pair| *pair != rank) {
+            return (6, rank);
+        }
+    }
+    if let Some(suited) = &flush_cards {
+        return (5, suited.iter().map(rank_value).max().unwrap_or(0));
+    }
+    if let Some(top_rank) = straight_top(cards) {
+        return (4, top_rank);
+    }
+    if let Some(&rank) = trips.first() {
+        return (3, rank);
+    }
+    match pairs.as_slice() {
+        [high, _, ..] => (2, *high),
+        [pair] => (1, *pair),
+        [] => (0, cards.iter().map(rank_value).max().unwrap_or(0)),
+    }
+}
+
+// highest rank of a run of five in `cards`, the ace also counting as a one
+fn straight_top(cards: &[Card]) -> Option<u8> {
+    let mut present = [false; ACE_HIGH as usize + 1];
+    for card in cards {
+        present[rank_value(card) as usize] = true;
+    }
+    present[1] = present[ACE_HIGH as usize];
+
+    (5..=ACE_HIGH).rev().find(|top| (top - 4..=*top).all(|rank| present[rank as usize]))
+}
@@ -1,6 +1,7 @@
 
 use crate::accounts::{ create_basic_aze_game_account, create_basic_aze_player_account };
-use crate::utils::{ create_aze_store_path, load_config };
+use crate::rng::AzeRng;
+use crate::utils::{ get_random_coin, load_config };
 use crate::notes::{
     create_send_card_note,
     create_play_bet_note,
@@ -11,40 +12,41 @@ use crate::notes::{
 };
 use crate::constants::CLIENT_CONFIG_FILE_NAME;
 use miden_client::client::rpc::NodeRpcClient;
-use miden_client::{ client, store };
-use miden_client::store::data_store::{ self, ClientDataStore };
 extern crate alloc;
-use alloc::collections::{ BTreeMap, BTreeSet };
+use alloc::collections::BTreeMap;
 
 use miden_client::{
     client::{
-        accounts::{ AccountStorageMode, AccountTemplate },
-        get_random_coin,
+        accounts::AccountStorageMode,
         rpc::TonicRpcClient,
         transactions::transaction_request::TransactionRequest,
         transactions::transaction_request,
         Client,
     },
-    config::{ ClientConfig, RpcConfig },
+    config::ClientConfig,
     errors::{ ClientError, NodeRpcClientError },
-    store::{ sqlite_store::SqliteStore, NoteFilter, Store, TransactionFilter, AuthInfo },
+    store::{ sqlite_store::SqliteStore, Store, AuthInfo },
 };
 
 use miden_lib::AuthScheme;
 use miden_objects::crypto::rand::FeltRng;
-use miden_objects::notes::NoteType;
+use miden_objects::notes::{ Note, NoteId, NoteType };
+use miden_objects::NoteError;
 use miden_objects::{
-    accounts::{ Account, AccountData, AccountId, AccountStub, AccountType, AuthData },
-    assets::TokenSymbol,
+    accounts::{
+        Account,
+        AccountId,
+        AccountStorageType,
+        AccountType,
+    },
     assembly::ProgramAst,
-    crypto::dsa::rpo_falcon512::SecretKey,
+    crypto::dsa::rpo_falcon512::{ PublicKey, SecretKey },
     Felt,
     Word,
 };
 use miden_objects::crypto::rand::RpoRandomCoin;
 use miden_objects::assets::Asset;
-use miden_tx::{ DataStore, TransactionExecutor };
-use rand::{ rngs::ThreadRng, Rng };
+use rand::Rng;
 use crate::storage::GameStorageSlotData;
 
 pub type AzeClient = Client<TonicRpcClient, RpoRandomCoin, SqliteStore>;
@@ -63,6 +65,7 @@ pub struct PlayBetTransactionData {
     sender_account_id: AccountId,
     target_account_id: AccountId,
     player_bet: u8,
+    note_type: NoteType,
 }
 
 #[derive(Clone)]
@@ -71,6 +74,7 @@ pub struct PlayRaiseTransactionData {
     sender_account_id: AccountId,
     target_account_id: AccountId,
     player_bet: u8,
+    note_type: NoteType,
 }
 
 #[derive(Clone)]
@@ -78,6 +82,7 @@ pub struct PlayCallTransactionData {
     asset: Asset,
     sender_account_id: AccountId,
     target_account_id: AccountId,
+    note_type: NoteType,
 }
 
 #[derive(Clone)]
@@ -85,6 +90,7 @@ pub struct PlayFoldTransactionData {
     asset: Asset,
     sender_account_id: AccountId,
     target_account_id: AccountId,
+    note_type: NoteType,
 }
 
 #[derive(Clone)]
@@ -92,12 +98,17 @@ pub struct PlayCheckTransactionData {
     asset: Asset,
     sender_account_id: AccountId,
     target_account_id: AccountId,
+    note_type: NoteType,
 }
 
 impl SendCardTransactionData {
     pub fn account_id(&self) -> AccountId {
         self.sender_account_id
     }
+    // the player the cards are dealt to, the deal note reaches them through the inbox
+    pub fn target_account_id(&self) -> AccountId {
+        self.target_account_id
+    }
     pub fn new(
         asset: Asset,
         sender_account_id: AccountId,
@@ -128,8 +139,13 @@ impl PlayBetTransactionData {
             sender_account_id,
             target_account_id,
             player_bet,
+            note_type: NoteType::Public,
         }
     }
+    pub fn with_note_type(mut self, note_type: NoteType) -> Self {
+        self.note_type = note_type;
+        self
+    }
 }
 
 impl PlayRaiseTransactionData {
@@ -147,8 +163,13 @@ impl PlayRaiseTransactionData {
             sender_account_id,
             target_account_id,
             player_bet,
+            note_type: NoteType::Public,
         }
     }
+    pub fn with_note_type(mut self, note_type: NoteType) -> Self {
+        self.note_type = note_type;
+        self
+    }
 }
 
 impl PlayCallTransactionData {
@@ -160,8 +181,13 @@ impl PlayCallTransactionData {
             asset,
             sender_account_id,
             target_account_id,
+            note_type: NoteType::Public,
         }
     }
+    pub fn with_note_type(mut self, note_type: NoteType) -> Self {
+        self.note_type = note_type;
+        self
+    }
 }
 
 impl PlayFoldTransactionData {
@@ -173,8 +199,13 @@ impl PlayFoldTransactionData {
             asset,
             sender_account_id,
             target_account_id,
+            note_type: NoteType::Public,
         }
     }
+    pub fn with_note_type(mut self, note_type: NoteType) -> Self {
+        self.note_type = note_type;
+        self
+    }
 }
 
 impl PlayCheckTransactionData {
@@ -186,13 +217,17 @@ impl PlayCheckTransactionData {
             asset,
             sender_account_id,
             target_account_id,
+            note_type: NoteType::Public,
         }
     }
+    pub fn with_note_type(mut self, note_type: NoteType) -> Self {
+        self.note_type = note_type;
+        self
+    }
 }
 
 pub trait AzeGameMethods {
     // fn get_tx_executor(&self) -> TransactionExecutor<ClientDataStore<D>>;
-    fn store(&self) -> SqliteStore;
     fn get_random_coin(&self) -> RpoRandomCoin;
     fn new_send_card_transaction(
         &mut self,
@@ -201,6 +236,28 @@ pub trait AzeGameMethods {
         target_account_id: AccountId,
         cards: &[[Felt; 4]; 2]
     ) -> Result<(), ClientError>;
+    fn build_aze_tx_request(
+        &mut self,
+        transaction_template: AzeTransactionTemplate
+    ) -> Result<TransactionRequest, ClientError>;
+    // request without transaction script, for players executing and proving on their own
+    fn build_aze_unsigned_tx_request(
+        &mut self,
+        transaction_template: AzeTransactionTemplate
+    ) -> Result<TransactionRequest, ClientError>;
+    // compiles the transaction script of an unsigned request with the sender's key
+    fn sign_aze_tx_request(
+        &mut self,
+        unsigned_request: TransactionRequest,
+        account_auth: AuthInfo
+    ) -> Result<TransactionRequest, ClientError>;
+    // consumes notes with the given key rather than the one this client's store keeps for the account
+    fn build_consume_notes_tx_request(
+        &mut self,
+        account_id: AccountId,
+        note_ids: Vec<NoteId>,
+        account_auth: AuthInfo
+    ) -> Result<TransactionRequest, ClientError>;
     fn build_aze_send_card_tx_request(
         &mut self,
         // auth_info: AuthInfo,
@@ -239,16 +296,30 @@ pub trait AzeGameMethods {
     fn new_aze_game_account(
         &mut self,
         mutable_code: bool,
-        rng: &mut ThreadRng,
+        rng: &mut AzeRng,
         account_storage_mode: AccountStorageMode,
         slot_data: GameStorageSlotData
     ) -> Result<(Account, Word), ClientError>;
     fn new_aze_player_account(
         &mut self,
         mutable_code: bool,
-        rng: &mut ThreadRng,
+        rng: &mut AzeRng,
         account_storage_mode: AccountStorageMode
     ) -> Result<(Account, Word), ClientError>;
+    // player account controlled by a key the caller keeps to itself
+    fn new_aze_player_account_with_key(
+        &mut self,
+        pub_key: PublicKey,
+        account_storage_mode: AccountStorageMode
+    ) -> Result<(Account, Word), ClientError>;
+    // game account controlled by a key the caller keeps to itself, e.g. in an `AzeKeyStore`
+    fn new_aze_game_account_with_key(
+        &mut self,
+        pub_key: PublicKey,
+        mutable_code: bool,
+        account_storage_mode: AccountStorageMode,
+        slot_data: GameStorageSlotData
+    ) -> Result<(Account, Word), ClientError>;
 }
 
 pub enum AzeAccountTemplate {
@@ -270,33 +341,35 @@ pub fn create_aze_client() -> AzeClient {
         .unwrap();
     current_dir.push(CLIENT_CONFIG_FILE_NAME);
     let client_config = load_config(current_dir.as_path()).unwrap();
+    create_aze_client_with_config(&client_config)
+}
+
+pub fn create_aze_client_with_config(client_config: &ClientConfig) -> AzeClient {
     let rng = get_random_coin();
 
     let rpc_endpoint = client_config.rpc.endpoint.to_string();
-    let store = SqliteStore::new((&client_config).into()).unwrap();
-    let executor_store = SqliteStore::new((&client_config).into()).unwrap();
+    let store = SqliteStore::new(client_config.into()).unwrap();
+    let executor_store = SqliteStore::new(client_config.into()).unwrap();
     AzeClient::new(TonicRpcClient::new(&rpc_endpoint), rng, store, executor_store, true)
 }
 
-impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S> {
-    fn store(&self) -> SqliteStore {
-        let mut current_dir = std::env
-            ::current_dir()
-            .map_err(|err| err.to_string())
-            .unwrap();
-        current_dir.push(CLIENT_CONFIG_FILE_NAME);
-        let client_config = load_config(current_dir.as_path()).unwrap();
+// whether the key this client holds for an account is the one the account authenticates with,
+// accounts created with a caller supplied key are only driven by their owner
+pub fn is_custodial(client: &AzeClient, account_id: AccountId) -> Result<bool, ClientError> {
+    let (account, _) = client.get_account(account_id)?;
+    let AuthInfo::RpoFalcon512(key) = client.get_account_auth(account_id)?;
+    let pub_key: Word = key.public_key().into();
 
-        let executor_store = SqliteStore::new((&client_config).into()).unwrap();
-        executor_store
-    }
+    Ok(Word::from(account.storage().get_item(0)) == pub_key)
+}
 
+impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S> {
     fn new_game_account(
         &mut self,
         template: AzeAccountTemplate,
         slot_data: Option<GameStorageSlotData>
     ) -> Result<(Account, Word), ClientError> {
-        let mut rng = rand::thread_rng();
+        let mut rng = AzeRng;
 
         let account_and_seed = (match template {
             AzeAccountTemplate::PlayerAccount { mutable_code, storage_mode } =>
@@ -310,8 +383,8 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S>
 
     fn new_aze_game_account(
         &mut self,
-        mutable_code: bool, // will remove it later on
-        rng: &mut ThreadRng,
+        mutable_code: bool,
+        rng: &mut AzeRng,
         account_storage_mode: AccountStorageMode,
         slot_data: GameStorageSlotData
     ) -> Result<(Account, Word), ClientError> {
@@ -328,10 +401,18 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S>
         // we need to use an initial seed to create the wallet account
         let init_seed: [u8; 32] = rng.gen();
 
+        // only updatable game accounts can go through `upgrade_game_account`
+        let account_type = if mutable_code {
+            AccountType::RegularAccountUpdatableCode
+        } else {
+            AccountType::RegularAccountImmutableCode
+        };
+
         let (account, seed) = create_basic_aze_game_account(
             init_seed,
             auth_scheme,
-            AccountType::RegularAccountImmutableCode,
+            account_type,
+            account_storage_type(account_storage_mode),
             slot_data
         ).unwrap();
 
@@ -343,7 +424,7 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S>
     fn new_aze_player_account(
         &mut self,
         mutable_code: bool,
-        rng: &mut ThreadRng,
+        rng: &mut AzeRng,
         account_storage_mode: AccountStorageMode
     ) -> Result<(Account, Word), ClientError> {
         if let AccountStorageMode::OnChain = account_storage_mode {
@@ -362,7 +443,8 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S>
         let (account, seed) = create_basic_aze_player_account(
             init_seed,
             auth_scheme,
-            AccountType::RegularAccountImmutableCode
+            AccountType::RegularAccountImmutableCode,
+            account_storage_type(account_storage_mode)
         ).unwrap();
 
         // will do insert account later on since there is some type mismatch due to miden object crate
@@ -370,203 +452,181 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S>
         Ok((account, seed))
     }
 
-    // TODO: include note_type as an argument here for now we are hardcoding it
-    fn build_aze_send_card_tx_request(
+    fn new_aze_player_account_with_key(
         &mut self,
-        // auth_info: AuthInfo,
-        transaction_template: AzeTransactionTemplate
-    ) -> Result<TransactionRequest, ClientError> {
-        let account_id = transaction_template.account_id();
-        let account_auth = self.store().get_account_auth(account_id)?;
+        pub_key: PublicKey,
+        account_storage_mode: AccountStorageMode
+    ) -> Result<(Account, Word), ClientError> {
+        // players bring their own key through the server, a failing request is better than a panic there
+        if let AccountStorageMode::OnChain = account_storage_mode {
+            return Err(
+                ClientError::NodeRpcClientError(
+                    NodeRpcClientError::RequestError(
+                        "new_aze_player_account_with_key".to_string(),
+                        "recording the account on chain is not supported yet".to_string()
+                    )
+                )
+            );
+        }
 
-        let (sender_account_id, target_account_id, cards, asset) = match transaction_template {
-            AzeTransactionTemplate::SendCard(
-                SendCardTransactionData { asset, sender_account_id, target_account_id, cards },
-            ) => (sender_account_id, target_account_id, cards, asset),
-            _ => panic!("Invalid transaction template"),
-        };
+        let mut rng = AzeRng;
 
-        let random_coin = self.get_random_coin();
+        let auth_scheme: AuthScheme = AuthScheme::RpoFalcon512 { pub_key };
 
-        let created_note = create_send_card_note(
-            self,
-            sender_account_id,
-            target_account_id,
-            [asset].to_vec(),
-            NoteType::Public,
-            random_coin,
-            cards
-        )?;
-
-        let recipient = created_note
-            .recipient_digest()
-            .iter()
-            .map(|x| x.as_int().to_string())
-            .collect::<Vec<_>>()
-            .join(".");
+        let init_seed: [u8; 32] = rng.gen();
+
+        let (account, seed) = create_basic_aze_player_account(
+            init_seed,
+            auth_scheme,
+            AccountType::RegularAccountImmutableCode,
+            account_storage_type(account_storage_mode)
+        ).map_err(ClientError::AccountError)?;
+
+        // the store wants a secret key with every account, so an unrelated one is kept here.
+        // it can never sign for this account, which is only driven through client-side proving
+        let placeholder_key = SecretKey::with_rng(&mut rng);
+        self.insert_account(&account, Some(seed), &AuthInfo::RpoFalcon512(placeholder_key))?;
+        Ok((account, seed))
+    }
 
-        let note_tag = created_note.metadata().tag().inner();
+    fn new_aze_game_account_with_key(
+        &mut self,
+        pub_key: PublicKey,
+        mutable_code: bool,
+        account_storage_mode: AccountStorageMode,
+        slot_data: GameStorageSlotData
+    ) -> Result<(Account, Word), ClientError> {
+        if let AccountStorageMode::OnChain = account_storage_mode {
+            return Err(
+                ClientError::NodeRpcClientError(
+                    NodeRpcClientError::RequestError(
+                        "new_aze_game_account_with_key".to_string(),
+                        "recording the account on chain is not supported yet".to_string()
+                    )
+                )
+            );
+        }
 
-        // TODO: remove this hardcoded note type
-        let note_type = NoteType::Public;
+        let mut rng = AzeRng;
 
-        let tx_script = ProgramAst::parse(
-            &transaction_request::AUTH_SEND_ASSET_SCRIPT
-                .replace("{recipient}", &recipient)
-                .replace("{note_type}", &Felt::new(note_type as u64).to_string())
-                .replace("{tag}", &Felt::new(note_tag.into()).to_string())
-                .replace("{asset}", &prepare_word(&asset.into()).to_string())
-        ).expect("shipped MASM is well-formed");
+        let auth_scheme: AuthScheme = AuthScheme::RpoFalcon512 { pub_key };
 
-        let tx_script = {
-            let script_inputs = vec![account_auth.into_advice_inputs()];
-            self.compile_tx_script(tx_script, script_inputs, vec![])?
+        let init_seed: [u8; 32] = rng.gen();
+
+        let account_type = if mutable_code {
+            AccountType::RegularAccountUpdatableCode
+        } else {
+            AccountType::RegularAccountImmutableCode
         };
 
-        println!("Created txn script");
+        let (account, seed) = create_basic_aze_game_account(
+            init_seed,
+            auth_scheme,
+            account_type,
+            account_storage_type(account_storage_mode),
+            slot_data
+        ).map_err(ClientError::AccountError)?;
 
-        Ok(
-            TransactionRequest::new(
-                sender_account_id,
-                BTreeMap::new(),
-                vec![created_note],
-                Some(tx_script)
-            )
-        )
+        // as for players, the store keeps an unrelated key that can never sign for the game
+        let placeholder_key = SecretKey::with_rng(&mut rng);
+        self.insert_account(&account, Some(seed), &AuthInfo::RpoFalcon512(placeholder_key))?;
+        Ok((account, seed))
     }
 
-    fn build_aze_play_bet_tx_request(
+    fn build_aze_tx_request(
         &mut self,
-        // auth_info: AuthInfo,
         transaction_template: AzeTransactionTemplate
     ) -> Result<TransactionRequest, ClientError> {
-        let account_id = transaction_template.account_id();
-        let account_auth = self.store().get_account_auth(account_id)?;
-
-        let (sender_account_id, target_account_id, asset, player_bet) = match transaction_template {
-            AzeTransactionTemplate::PlayBet(
-                PlayBetTransactionData {
-                    asset,
-                    sender_account_id,
-                    target_account_id,
-                    player_bet,
-                },
-            ) => (sender_account_id, target_account_id, asset, player_bet),
-            _ => panic!("Invalid transaction template"),
-        };
+        let account_auth = self.get_account_auth(transaction_template.account_id())?;
+        let (sender_account_id, created_note, asset) = create_aze_note(self, transaction_template)?;
 
-        let random_coin = self.get_random_coin();
+        build_note_tx_request(self, sender_account_id, created_note, asset, account_auth)
+    }
 
-        let created_note = create_play_bet_note(
-            self,
-            sender_account_id,
-            target_account_id,
-            [asset].to_vec(),
-            NoteType::Public,
-            random_coin,
-            player_bet
-        )?;
-
-        let recipient = created_note
-            .recipient_digest()
-            .iter()
-            .map(|x| x.as_int().to_string())
-            .collect::<Vec<_>>()
-            .join(".");
+    fn build_aze_unsigned_tx_request(
+        &mut self,
+        transaction_template: AzeTransactionTemplate
+    ) -> Result<TransactionRequest, ClientError> {
+        let (sender_account_id, created_note, _) = create_aze_note(self, transaction_template)?;
 
-        let note_tag = created_note.metadata().tag().inner();
-        let note_type = NoteType::Public;
+        // the script embeds the sender's key, so it is left for the signer to compile
+        Ok(TransactionRequest::new(sender_account_id, BTreeMap::new(), vec![created_note], None))
+    }
 
-        let tx_script = ProgramAst::parse(
-            &transaction_request::AUTH_SEND_ASSET_SCRIPT
-                .replace("{recipient}", &recipient)
-                .replace("{note_type}", &Felt::new(note_type as u64).to_string())
-                .replace("{tag}", &Felt::new(note_tag.into()).to_string())
-                .replace("{asset}", &prepare_word(&asset.into()).to_string())
-        ).expect("shipped MASM is well-formed");
+    fn sign_aze_tx_request(
+        &mut self,
+        unsigned_request: TransactionRequest,
+        account_auth: AuthInfo
+    ) -> Result<TransactionRequest, ClientError> {
+        let sender_account_id = unsigned_request.account_id();
+
+        // aze requests create a single note carrying a single asset
+        let created_note = unsigned_request
+            .expected_output_notes()
+            .first()
+            .cloned()
+            .ok_or(ClientError::MissingOutputNotes(vec![]))?;
+        let asset = *created_note
+            .assets()
+            .iter()
+            .next()
+            .ok_or(ClientError::NoteError(NoteError::EmptyAssetList))?;
 
+        build_note_tx_request(self, sender_account_id, created_note, asset, account_auth)
+    }
+
+    fn build_consume_notes_tx_request(
+        &mut self,
+        account_id: AccountId,
+        note_ids: Vec<NoteId>,
+        account_auth: AuthInfo
+    ) -> Result<TransactionRequest, ClientError> {
+        let tx_script = ProgramAst::parse(transaction_request::AUTH_CONSUME_NOTES_SCRIPT).expect(
+            "shipped MASM is well-formed"
+        );
         let tx_script = {
             let script_inputs = vec![account_auth.into_advice_inputs()];
             self.compile_tx_script(tx_script, script_inputs, vec![])?
         };
+        let input_notes = note_ids
+            .into_iter()
+            .map(|note_id| (note_id, None))
+            .collect();
 
-        println!("Created txn script");
-
-        Ok(
-            TransactionRequest::new(
-                sender_account_id,
-                BTreeMap::new(),
-                vec![created_note],
-                Some(tx_script)
-            )
-        )
+        Ok(TransactionRequest::new(account_id, input_notes, vec![], Some(tx_script)))
     }
 
-    fn build_aze_play_raise_tx_request(
+    fn build_aze_send_card_tx_request(
         &mut self,
         // auth_info: AuthInfo,
         transaction_template: AzeTransactionTemplate
     ) -> Result<TransactionRequest, ClientError> {
-        let account_id = transaction_template.account_id();
-        let account_auth = self.store().get_account_auth(account_id)?;
-
-        let (sender_account_id, target_account_id, asset, player_bet) = match transaction_template {
-            AzeTransactionTemplate::PlayRaise(
-                PlayRaiseTransactionData {
-                    asset,
-                    sender_account_id,
-                    target_account_id,
-                    player_bet,
-                },
-            ) => (sender_account_id, target_account_id, asset, player_bet),
+        match transaction_template {
+            AzeTransactionTemplate::SendCard(_) => self.build_aze_tx_request(transaction_template),
             _ => panic!("Invalid transaction template"),
-        };
-
-        let random_coin = self.get_random_coin();
-
-        let created_note = create_play_raise_note(
-            self,
-            sender_account_id,
-            target_account_id,
-            [asset].to_vec(),
-            NoteType::Public,
-            random_coin,
-            player_bet
-        )?;
-
-        let recipient = created_note
-            .recipient_digest()
-            .iter()
-            .map(|x| x.as_int().to_string())
-            .collect::<Vec<_>>()
-            .join(".");
-
-        let note_tag = created_note.metadata().tag().inner();
-        let note_type = NoteType::Public;
-
-        let tx_script = ProgramAst::parse(
-            &transaction_request::AUTH_SEND_ASSET_SCRIPT
-                .replace("{recipient}", &recipient)
-                .replace("{note_type}", &Felt::new(note_type as u64).to_string())
-                .replace("{tag}", &Felt::new(note_tag.into()).to_string())
-                .replace("{asset}", &prepare_word(&asset.into()).to_string())
-        ).expect("shipped MASM is well-formed");
-
-        let tx_script = {
-            let script_inputs = vec![account_auth.into_advice_inputs()];
-            self.compile_tx_script(tx_script, script_inputs, vec![])?
-        };
+        }
+    }
 
-        println!("Created txn script");
+    fn build_aze_play_bet_tx_request(
+        &mut self,
+        // auth_info: AuthInfo,
+        transaction_template: AzeTransactionTemplate
+    ) -> Result<TransactionRequest, ClientError> {
+        match transaction_template {
+            AzeTransactionTemplate::PlayBet(_) => self.build_aze_tx_request(transaction_template),
+            _ => panic!("Invalid transaction template"),
+        }
+    }
 
-        Ok(
-            TransactionRequest::new(
-                sender_account_id,
-                BTreeMap::new(),
-                vec![created_note],
-                Some(tx_script)
-            )
-        )
+    fn build_aze_play_raise_tx_request(
+        &mut self,
+        // auth_info: AuthInfo,
+        transaction_template: AzeTransactionTemplate
+    ) -> Result<TransactionRequest, ClientError> {
+        match transaction_template {
+            AzeTransactionTemplate::PlayRaise(_) => self.build_aze_tx_request(transaction_template),
+            _ => panic!("Invalid transaction template"),
+        }
     }
 
     fn build_aze_play_call_tx_request(
@@ -574,60 +634,10 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S>
         // auth_info: AuthInfo,
         transaction_template: AzeTransactionTemplate
     ) -> Result<TransactionRequest, ClientError> {
-        let account_id = transaction_template.account_id();
-        let account_auth = self.store().get_account_auth(account_id)?;
-
-        let (sender_account_id, target_account_id, asset) = match transaction_template {
-            AzeTransactionTemplate::PlayCall(
-                PlayCallTransactionData { asset, sender_account_id, target_account_id },
-            ) => (sender_account_id, target_account_id, asset),
+        match transaction_template {
+            AzeTransactionTemplate::PlayCall(_) => self.build_aze_tx_request(transaction_template),
             _ => panic!("Invalid transaction template"),
-        };
-
-        let random_coin = self.get_random_coin();
-
-        let created_note = create_play_call_note(
-            self,
-            sender_account_id,
-            target_account_id,
-            [asset].to_vec(),
-            NoteType::Public,
-            random_coin
-        )?;
-
-        let recipient = created_note
-            .recipient_digest()
-            .iter()
-            .map(|x| x.as_int().to_string())
-            .collect::<Vec<_>>()
-            .join(".");
-
-        let note_tag = created_note.metadata().tag().inner();
-        let note_type = NoteType::Public;
-
-        let tx_script = ProgramAst::parse(
-            &transaction_request::AUTH_SEND_ASSET_SCRIPT
-                .replace("{recipient}", &recipient)
-                .replace("{note_type}", &Felt::new(note_type as u64).to_string())
-                .replace("{tag}", &Felt::new(note_tag.into()).to_string())
-                .replace("{asset}", &prepare_word(&asset.into()).to_string())
-        ).expect("shipped MASM is well-formed");
-
-        let tx_script = {
-            let script_inputs = vec![account_auth.into_advice_inputs()];
-            self.compile_tx_script(tx_script, script_inputs, vec![])?
-        };
-
-        println!("Created txn script");
-
-        Ok(
-            TransactionRequest::new(
-                sender_account_id,
-                BTreeMap::new(),
-                vec![created_note],
-                Some(tx_script)
-            )
-        )
+        }
     }
 
     fn build_aze_play_fold_tx_request(
@@ -635,60 +645,10 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S>
         // auth_info: AuthInfo,
         transaction_template: AzeTransactionTemplate
     ) -> Result<TransactionRequest, ClientError> {
-        let account_id = transaction_template.account_id();
-        let account_auth = self.store().get_account_auth(account_id)?;
-
-        let (sender_account_id, target_account_id, asset) = match transaction_template {
-            AzeTransactionTemplate::PlayFold(
-                PlayFoldTransactionData { asset, sender_account_id, target_account_id },
-            ) => (sender_account_id, target_account_id, asset),
+        match transaction_template {
+            AzeTransactionTemplate::PlayFold(_) => self.build_aze_tx_request(transaction_template),
             _ => panic!("Invalid transaction template"),
-        };
-
-        let random_coin = self.get_random_coin();
-
-        let created_note = create_play_fold_note(
-            self,
-            sender_account_id,
-            target_account_id,
-            [asset].to_vec(),
-            NoteType::Public,
-            random_coin
-        )?;
-
-        let recipient = created_note
-            .recipient_digest()
-            .iter()
-            .map(|x| x.as_int().to_string())
-            .collect::<Vec<_>>()
-            .join(".");
-
-        let note_tag = created_note.metadata().tag().inner();
-        let note_type = NoteType::Public;
-
-        let tx_script = ProgramAst::parse(
-            &transaction_request::AUTH_SEND_ASSET_SCRIPT
-                .replace("{recipient}", &recipient)
-                .replace("{note_type}", &Felt::new(note_type as u64).to_string())
-                .replace("{tag}", &Felt::new(note_tag.into()).to_string())
-                .replace("{asset}", &prepare_word(&asset.into()).to_string())
-        ).expect("shipped MASM is well-formed");
-
-        let tx_script = {
-            let script_inputs = vec![account_auth.into_advice_inputs()];
-            self.compile_tx_script(tx_script, script_inputs, vec![])?
-        };
-
-        println!("Created txn script");
-
-        Ok(
-            TransactionRequest::new(
-                sender_account_id,
-                BTreeMap::new(),
-                vec![created_note],
-                Some(tx_script)
-            )
-        )
+        }
     }
 
     fn build_aze_play_check_tx_request(
@@ -696,60 +656,10 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S>
         // auth_info: AuthInfo,
         transaction_template: AzeTransactionTemplate
     ) -> Result<TransactionRequest, ClientError> {
-        let account_id = transaction_template.account_id();
-        let account_auth = self.store().get_account_auth(account_id)?;
-
-        let (sender_account_id, target_account_id, asset) = match transaction_template {
-            AzeTransactionTemplate::PlayCheck(
-                PlayCheckTransactionData { asset, sender_account_id, target_account_id },
-            ) => (sender_account_id, target_account_id, asset),
+        match transaction_template {
+            AzeTransactionTemplate::PlayCheck(_) => self.build_aze_tx_request(transaction_template),
             _ => panic!("Invalid transaction template"),
-        };
-
-        let random_coin = self.get_random_coin();
-
-        let created_note = create_play_check_note(
-            self,
-            sender_account_id,
-            target_account_id,
-            [asset].to_vec(),
-            NoteType::Public,
-            random_coin
-        )?;
-
-        let recipient = created_note
-            .recipient_digest()
-            .iter()
-            .map(|x| x.as_int().to_string())
-            .collect::<Vec<_>>()
-            .join(".");
-
-        let note_tag = created_note.metadata().tag().inner();
-        let note_type = NoteType::Public;
-
-        let tx_script = ProgramAst::parse(
-            &transaction_request::AUTH_SEND_ASSET_SCRIPT
-                .replace("{recipient}", &recipient)
-                .replace("{note_type}", &Felt::new(note_type as u64).to_string())
-                .replace("{tag}", &Felt::new(note_tag.into()).to_string())
-                .replace("{asset}", &prepare_word(&asset.into()).to_string())
-        ).expect("shipped MASM is well-formed");
-
-        let tx_script = {
-            let script_inputs = vec![account_auth.into_advice_inputs()];
-            self.compile_tx_script(tx_script, script_inputs, vec![])?
-        };
-
-        println!("Created txn script");
-
-        Ok(
-            TransactionRequest::new(
-                sender_account_id,
-                BTreeMap::new(),
-                vec![created_note],
-                Some(tx_script)
-            )
-        )
+        }
     }
 
     fn new_send_card_transaction(
@@ -764,11 +674,7 @@ impl<N: NodeRpcClient, R: FeltRng, S: Store> AzeGameMethods for Client<N, R, S>
     }
 
     fn get_random_coin(&self) -> RpoRandomCoin {
-        // TODO: Initialize coin status once along with the client and persist status for retrieval
-        let mut rng = rand::thread_rng();
-        let coin_seed: [u64; 4] = rng.gen();
-
-        RpoRandomCoin::new(coin_seed.map(Felt::new))
+        get_random_coin()
     }
 }
 
@@ -796,6 +702,155 @@ impl AzeTransactionTemplate {
     }
 }
 
+// builds the note a transaction template sends, along with its sender and the asset it carries
+fn create_aze_note<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    transaction_template: AzeTransactionTemplate
+) -> Result<(AccountId, Note, Asset), ClientError> {
+    let random_coin = client.get_random_coin();
+
+    let (sender_account_id, created_note, asset) = match transaction_template {
+        AzeTransactionTemplate::SendCard(
+            SendCardTransactionData { asset, sender_account_id, cards, .. },
+        ) => {
+            let note = create_send_card_note(
+                sender_account_id,
+                [asset].to_vec(),
+                random_coin,
+                cards
+            )?;
+            (sender_account_id, note, asset)
+        }
+        AzeTransactionTemplate::PlayBet(
+            PlayBetTransactionData {
+                asset,
+                sender_account_id,
+                target_account_id,
+                player_bet,
+                note_type,
+            },
+        ) => {
+            let note = create_play_bet_note(
+                sender_account_id,
+                target_account_id,
+                [asset].to_vec(),
+                note_type,
+                random_coin,
+                player_bet
+            )?;
+            (sender_account_id, note, asset)
+        }
+        AzeTransactionTemplate::PlayRaise(
+            PlayRaiseTransactionData {
+                asset,
+                sender_account_id,
+                target_account_id,
+                player_bet,
+                note_type,
+            },
+        ) => {
+            let note = create_play_raise_note(
+                sender_account_id,
+                target_account_id,
+                [asset].to_vec(),
+                note_type,
+                random_coin,
+                player_bet
+            )?;
+            (sender_account_id, note, asset)
+        }
+        AzeTransactionTemplate::PlayCall(
+            PlayCallTransactionData { asset, sender_account_id, target_account_id, note_type },
+        ) => {
+            let note = create_play_call_note(
+                sender_account_id,
+                target_account_id,
+                [asset].to_vec(),
+                note_type,
+                random_coin
+            )?;
+            (sender_account_id, note, asset)
+        }
+        AzeTransactionTemplate::PlayFold(
+            PlayFoldTransactionData { asset, sender_account_id, target_account_id, note_type },
+        ) => {
+            let note = create_play_fold_note(
+                sender_account_id,
+                target_account_id,
+                [asset].to_vec(),
+                note_type,
+                random_coin
+            )?;
+            (sender_account_id, note, asset)
+        }
+        AzeTransactionTemplate::PlayCheck(
+            PlayCheckTransactionData { asset, sender_account_id, target_account_id, note_type },
+        ) => {
+            let note = create_play_check_note(
+                sender_account_id,
+                target_account_id,
+                [asset].to_vec(),
+                note_type,
+                random_coin
+            )?;
+            (sender_account_id, note, asset)
+        }
+    };
+
+    Ok((sender_account_id, created_note, asset))
+}
+
+// wraps a created note into a request whose script sends it out of the sender account
+fn build_note_tx_request<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    sender_account_id: AccountId,
+    created_note: Note,
+    asset: Asset,
+    account_auth: AuthInfo
+) -> Result<TransactionRequest, ClientError> {
+    let recipient = created_note
+        .recipient_digest()
+        .iter()
+        .map(|x| x.as_int().to_string())
+        .collect::<Vec<_>>()
+        .join(".");
+
+    let note_tag = created_note.metadata().tag().inner();
+
+    let note_type = created_note.metadata().note_type();
+
+    let tx_script = ProgramAst::parse(
+        &transaction_request::AUTH_SEND_ASSET_SCRIPT
+            .replace("{recipient}", &recipient)
+            .replace("{note_type}", &Felt::new(note_type as u64).to_string())
+            .replace("{tag}", &Felt::new(note_tag.into()).to_string())
+            .replace("{asset}", &prepare_word(&asset.into()).to_string())
+    ).expect("shipped MASM is well-formed");
+
+    let tx_script = {
+        let script_inputs = vec![account_auth.into_advice_inputs()];
+        client.compile_tx_script(tx_script, script_inputs, vec![])?
+    };
+
+
+    Ok(
+        TransactionRequest::new(
+            sender_account_id,
+            BTreeMap::new(),
+            vec![created_note],
+            Some(tx_script)
+        )
+    )
+}
+
+// the id of a local account marks it off-chain, so the node only ever records its state commitment
+fn account_storage_type(account_storage_mode: AccountStorageMode) -> AccountStorageType {
+    match account_storage_mode {
+        AccountStorageMode::Local => AccountStorageType::OffChain,
+        AccountStorageMode::OnChain => AccountStorageType::OnChain,
+    }
+}
+
 pub(crate) fn prepare_word(word: &Word) -> String {
     word.iter()
         .map(|x| x.as_int().to_string())
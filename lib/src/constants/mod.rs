@@ -14,13 +14,27 @@ pub const SMALL_BLIND_AMOUNT: u8 = 5;
 pub const PLAYER_INITIAL_BALANCE: u8 = 30;
 pub const HIGHEST_BET: u8 = SMALL_BLIND_AMOUNT;
 pub const NO_OF_PLAYERS: u8 = 4;
+pub const MIN_PLAYERS: u8 = 2;
+pub const MAX_PLAYERS: u8 = 9;
 pub const FLOP_INDEX: u8 = NO_OF_PLAYERS * 2 + 1;
 pub const PLAYER_BET_OFFSET: u8 = 3;
+pub const PLAYER_BALANCE_OFFSET: u8 = 4;
 pub const IS_FOLD_OFFSET: u8 = 10;
 pub const PLAYER_STATS_SLOTS: u8 = 13;
 pub const FIRST_PLAYER_INDEX: u8 = 64;
 pub const LAST_PLAYER_INDEX: u8 = FIRST_PLAYER_INDEX + (NO_OF_PLAYERS - 1) * PLAYER_STATS_SLOTS;
+// the 52 cards of the deck, in the order they are dealt
+pub const FIRST_DECK_SLOT: u8 = 1;
+pub const LAST_DECK_SLOT: u8 = 52;
+pub const FLOP_INDEX_SLOT: u8 = 53;
+pub const SMALL_BLIND_SLOT: u8 = 54;
+pub const BIG_BLIND_SLOT: u8 = 55;
+pub const BUY_IN_SLOT: u8 = 56;
+pub const NO_OF_PLAYERS_SLOT: u8 = 57;
 pub const RAISER_INDEX_SLOT: u8 = 58;
+// layout version of the game storage, tables created before it was introduced read as 0
+pub const CONTRACT_VERSION_SLOT: u8 = 59;
+pub const GAME_STORAGE_VERSION: u8 = 1;
 pub const CURRENT_TURN_INDEX_SLOT: u8 = 60;
 pub const HIGHEST_BET_SLOT: u8 = 61;
 pub const CURRENT_PHASE_SLOT: u8 = 62;
@@ -0,0 +1,110 @@
+use std::sync::OnceLock;
+
+use miden_client::{ client::transactions::transaction_request::TransactionRequest, store::AuthInfo };
+use miden_objects::{
+    accounts::{ AccountCode, AccountId, AccountStorage },
+    assembly::{ Library, MaslLibrary, ModuleAst, ProgramAst },
+    utils::Deserializable,
+    Digest,
+    Word,
+};
+
+use crate::client::{ prepare_word, AzeClient };
+use crate::executor::{ execute_tx_and_sync, ExecutorError };
+use crate::storage::migrate_game_storage;
+
+// MAST roots of the procedures exported by the poker contracts, e.g. `GAME_PLAY_BET`, along with
+// `GAME_PROCEDURES` and `PLAYER_PROCEDURES` listing them per contract
+include!(concat!(env!("OUT_DIR"), "/poker_procedures.rs"));
+
+const UPGRADE_GAME_SCRIPT: &str =
+    "
+    begin
+{migrations}
+        push.{code_root}
+        call.{upgrade}
+    end
+";
+
+/// The `poker` library assembled by build.rs from contracts/core, one module per contract.
+pub fn poker_library() -> &'static MaslLibrary {
+    static POKER_LIBRARY: OnceLock<MaslLibrary> = OnceLock::new();
+
+    POKER_LIBRARY.get_or_init(|| {
+        let bytes = include_bytes!(concat!(env!("OUT_DIR"), "/assets/poker.masl"));
+        MaslLibrary::read_from_bytes(bytes).expect("poker library is assembled by build.rs")
+    })
+}
+
+// AST of a contract of the poker library, `game` or `player`
+pub fn poker_module(contract: &str) -> ModuleAst {
+    let path = format!("poker::{contract}");
+    poker_library()
+        .modules()
+        .find(|module| module.path.as_ref() == path)
+        .map(|module| module.ast.clone())
+        .unwrap_or_else(|| panic!("poker library has no {path} module"))
+}
+
+pub fn procedure_root(root: &str) -> Digest {
+    Digest::try_from(root).expect("procedure roots are generated by build.rs")
+}
+
+/// Whether an account runs the game contract of this build.
+///
+/// Game notes and tx scripts rely on its procedures, so a table created by an older build is
+/// only played once its code has the same roots.
+pub fn is_compatible_game_code(code: &AccountCode) -> bool {
+    GAME_PROCEDURES.iter().all(|(_, root)| code.procedures().contains(&procedure_root(root)))
+}
+
+pub fn is_compatible_player_code(code: &AccountCode) -> bool {
+    PLAYER_PROCEDURES.iter().all(|(_, root)| code.procedures().contains(&procedure_root(root)))
+}
+
+/// Moves an updatable game account to `new_code`, migrating its storage to the layout of this build.
+///
+/// The storage writes and the code change go in a single transaction signed with `game_auth`, see
+/// [crate::keystore::AzeKeyStore::account_auth], so a table never runs new code over an old layout.
+// TODO: account deltas don't carry code changes yet, so the client store keeps running the previous
+// code until the account is imported again
+pub async fn upgrade_game_account(
+    client: &mut AzeClient,
+    game_account_id: AccountId,
+    game_auth: AuthInfo,
+    new_code: &AccountCode
+) -> Result<(), ExecutorError> {
+    let (game_account, _) = client.get_account(game_account_id)?;
+
+    let tx_script = ProgramAst::parse(
+        &upgrade_game_script(game_account.storage(), new_code.root())
+    ).expect("shipped MASM is well-formed");
+    let tx_script = {
+        let script_inputs = vec![game_auth.into_advice_inputs()];
+        client.compile_tx_script(tx_script, script_inputs, vec![])?
+    };
+    let tx_request = TransactionRequest::new(
+        game_account_id,
+        Default::default(),
+        vec![],
+        Some(tx_script)
+    );
+    execute_tx_and_sync(client, tx_request).await
+}
+
+// tx script running the storage migrations of `storage`, then setting `new_code_root` as the code
+// of the game account
+pub(crate) fn upgrade_game_script(storage: &AccountStorage, new_code_root: Digest) -> String {
+    let migrate_item = procedure_root(GAME_MIGRATE_ITEM).to_hex();
+    let migrations = migrate_game_storage(storage)
+        .iter()
+        .map(|(index, value)| {
+            format!("        push.{} push.{index} call.{migrate_item}\n", prepare_word(value))
+        })
+        .collect::<String>();
+
+    let code_root: Word = new_code_root.into();
+    UPGRADE_GAME_SCRIPT.replace("{migrations}", &migrations)
+        .replace("{code_root}", &prepare_word(&code_root))
+        .replace("{upgrade}", &procedure_root(GAME_UPGRADE).to_hex())
+}
@@ -0,0 +1,191 @@
+use std::fmt;
+
+use aze_types::actions::{ GameState, PlayerState, PokerAction };
+
+// seat whose check in the pre-flop closes the round, the big blind
+const BIG_BLIND_SEAT: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BettingError {
+    // every seat has folded, there is nobody left to take the turn
+    NoActivePlayer,
+    PlayerFolded,
+    InsufficientBalance,
+    // a call when the player's bet is already above the highest bet
+    InvalidCall,
+}
+
+impl fmt::Display for BettingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BettingError::NoActivePlayer => write!(f, "no active player left at the table"),
+            BettingError::PlayerFolded => write!(f, "player has already folded"),
+            BettingError::InsufficientBalance => write!(f, "player balance does not cover the bet"),
+            BettingError::InvalidCall => write!(f, "player bet is above the highest bet"),
+        }
+    }
+}
+
+/// Betting state of a table, played out in Rust the same way the game contract does it.
+///
+/// It is the reference the MASM game account is checked against: turn rotation skipping folded
+/// seats, raiser tracking, the check counter and moving on to the next phase. An action the
+/// contract would reject, or would compute garbage for, is an error here and leaves the state
+/// untouched.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BettingRound {
+    pub current_turn: u8,
+    pub raiser: Option<u8>,
+    pub highest_bet: u8,
+    pub current_phase: u8,
+    pub check_count: u8,
+    pub players: Vec<PlayerState>,
+}
+
+impl From<&GameState> for BettingRound {
+    fn from(state: &GameState) -> Self {
+        Self {
+            current_turn: state.current_turn,
+            raiser: state.raiser,
+            highest_bet: state.highest_bet,
+            current_phase: state.current_phase,
+            check_count: state.check_count,
+            players: state.players.clone(),
+        }
+    }
+}
+
+impl BettingRound {
+    /// Plays `action` for the seat holding the turn, `amount` is only read by bets and raises.
+    pub fn apply(&mut self, action: PokerAction, amount: u8) -> Result<(), BettingError> {
+        let mut next = self.clone();
+        match action {
+            PokerAction::Bet => next.play_bet(amount)?,
+            PokerAction::Raise => next.play_raise(amount)?,
+            PokerAction::Call => next.play_call()?,
+            PokerAction::Fold => next.play_fold()?,
+            PokerAction::Check => next.play_check()?,
+        }
+        *self = next;
+        Ok(())
+    }
+
+    // chips the seat holding the turn has to put in to match the highest bet
+    pub fn call_amount(&self) -> Result<u8, BettingError> {
+        let player = &self.players[self.current_turn as usize];
+        let difference = self.highest_bet
+            .checked_sub(player.bet)
+            .ok_or(BettingError::InvalidCall)?;
+        if difference > player.balance {
+            return Err(BettingError::InsufficientBalance);
+        }
+        Ok(difference)
+    }
+
+    /// Moves the turn to the next seat that has not folded, going round after the last one.
+    pub fn update_current_turn(&mut self) -> Result<(), BettingError> {
+        let player_count = self.players.len() as u8;
+        let mut seat = self.current_turn;
+        for _ in 0..player_count {
+            seat = (seat + 1) % player_count;
+            if !self.players[seat as usize].has_folded {
+                self.current_turn = seat;
+                return Ok(());
+            }
+        }
+        Err(BettingError::NoActivePlayer)
+    }
+
+    fn play_bet(&mut self, amount: u8) -> Result<(), BettingError> {
+        self.put_in(amount)?;
+        self.update_current_turn()
+    }
+
+    fn play_raise(&mut self, amount: u8) -> Result<(), BettingError> {
+        self.put_in(amount)?;
+        self.raiser = Some(self.current_turn);
+        self.update_current_turn()
+    }
+
+    fn play_call(&mut self) -> Result<(), BettingError> {
+        let difference = self.call_amount()?;
+        let highest_bet = self.highest_bet;
+        let player = &mut self.players[self.current_turn as usize];
+        player.balance -= difference;
+        player.bet = highest_bet;
+
+        self.update_current_turn()?;
+        self.close_raise_round()
+    }
+
+    fn play_fold(&mut self) -> Result<(), BettingError> {
+        let player = &mut self.players[self.current_turn as usize];
+        if player.has_folded {
+            return Err(BettingError::PlayerFolded);
+        }
+        player.has_folded = true;
+
+        self.update_current_turn()?;
+        self.close_raise_round()
+    }
+
+    fn play_check(&mut self) -> Result<(), BettingError> {
+        let acting_seat = self.current_turn;
+
+        // a check only counts when every active seat before it has checked as well
+        let active_before = self.players[..acting_seat as usize]
+            .iter()
+            .filter(|player| !player.has_folded)
+            .count() as u8;
+        if self.check_count == active_before {
+            self.check_count += 1;
+        }
+
+        self.update_current_turn()?;
+
+        let active_players = self.players
+            .iter()
+            .filter(|player| !player.has_folded)
+            .count() as u8;
+        let is_big_blind_preflop = acting_seat == BIG_BLIND_SEAT && self.current_phase == 0;
+
+        if self.check_count == active_players || is_big_blind_preflop {
+            self.current_phase += 1;
+            self.check_count = 0;
+            self.restart_turn()?;
+        }
+        Ok(())
+    }
+
+    // the bet or raise amount becomes the highest bet, and the player's bet
+    fn put_in(&mut self, amount: u8) -> Result<(), BettingError> {
+        let player = &mut self.players[self.current_turn as usize];
+        if amount > player.balance {
+            return Err(BettingError::InsufficientBalance);
+        }
+        player.bet = amount;
+        player.balance -= amount;
+        self.highest_bet = amount;
+        Ok(())
+    }
+
+    // once the turn gets back to the raiser everybody has answered the raise
+    fn close_raise_round(&mut self) -> Result<(), BettingError> {
+        if self.raiser != Some(self.current_turn) {
+            return Ok(());
+        }
+        self.current_phase += 1;
+        self.raiser = None;
+        self.check_count = 0;
+        self.restart_turn()
+    }
+
+    // a new phase starts from the first seat still playing
+    fn restart_turn(&mut self) -> Result<(), BettingError> {
+        self.current_turn = 0;
+        if self.players[0].has_folded {
+            self.update_current_turn()?;
+        }
+        Ok(())
+    }
+}
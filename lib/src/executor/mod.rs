@@ -1,38 +1,194 @@
+use std::fmt;
+use std::time::Duration;
+
 use crate::client::AzeClient;
 use miden_client::{
-    client::transactions::transaction_request::TransactionRequest,
-    store::{TransactionFilter},
+    client::{
+        rpc::NodeRpcClient,
+        transactions::{ transaction_request::TransactionRequest, TransactionResult },
+        Client,
+    },
+    config::ClientConfig,
+    errors::ClientError,
+    store::{ sqlite_store::SqliteStore, NoteFilter, Store, TransactionFilter },
+};
+use miden_objects::{
+    accounts::AccountId,
+    crypto::rand::FeltRng,
+    notes::{ Note, NoteId },
+    transaction::{ ProvenTransaction, TransactionId },
+    utils::{ ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader },
 };
+use miden_tx::{ ProvingOptions, TransactionProver };
+use tokio::{ sync::Mutex, time::sleep };
+
+// how long a submitted transaction is waited for: the node is polled once per interval, for that many attempts
+const TX_COMMIT_ATTEMPTS: u32 = 40;
+const TX_COMMIT_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Debug)]
+pub enum ExecutorError {
+    Client(ClientError),
+    // the node did not commit the transaction within `TX_COMMIT_ATTEMPTS` syncs
+    Timeout(TransactionId),
+}
+
+impl fmt::Display for ExecutorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutorError::Client(err) => write!(f, "client error: {err}"),
+            ExecutorError::Timeout(transaction_id) =>
+                write!(f, "transaction {} was not committed in time", transaction_id.to_hex()),
+        }
+    }
+}
+
+impl From<ClientError> for ExecutorError {
+    fn from(err: ClientError) -> Self {
+        ExecutorError::Client(err)
+    }
+}
+
+/// Executes, proves and submits `tx_request`, then syncs until the node has committed it.
+///
+/// Returns the error of the first step that fails, e.g. a transaction the contracts reject, or
+/// `Timeout` if the transaction is still not committed after `TX_COMMIT_ATTEMPTS` syncs.
+pub async fn execute_tx_and_sync<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    tx_request: TransactionRequest
+) -> Result<(), ExecutorError> {
+    let transaction_id = submit_tx(client, tx_request).await?;
+
+    for _ in 0..TX_COMMIT_ATTEMPTS {
+        if is_tx_committed(client, transaction_id).await? {
+            return Ok(());
+        }
+        sleep(TX_COMMIT_INTERVAL).await;
+    }
+
+    Err(ExecutorError::Timeout(transaction_id))
+}
+
+/// Same as [execute_tx_and_sync] for a client shared with other tasks.
+///
+/// The client is only locked while the transaction is submitted and while each sync runs, so it keeps
+/// serving other requests while the node commits the transaction.
+pub async fn execute_shared_tx_and_sync(
+    client: &Mutex<AzeClient>,
+    tx_request: TransactionRequest
+) -> Result<(), ExecutorError> {
+    let transaction_id = submit_tx(&mut *client.lock().await, tx_request).await?;
 
+    for _ in 0..TX_COMMIT_ATTEMPTS {
+        if is_tx_committed(&mut *client.lock().await, transaction_id).await? {
+            return Ok(());
+        }
+        sleep(TX_COMMIT_INTERVAL).await;
+    }
 
-pub async fn execute_tx_and_sync(client: &mut AzeClient, tx_request: TransactionRequest) {
-    println!("Executing transaction...");
-    client.sync_state().await.unwrap();
-    let transaction_execution_result = client.new_transaction(tx_request.clone()).unwrap();
-    println!("Got execution result");
+    Err(ExecutorError::Timeout(transaction_id))
+}
+
+// executes and proves the transaction against an up to date store and sends it to the node
+async fn submit_tx<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    tx_request: TransactionRequest
+) -> Result<TransactionId, ClientError> {
+    log::debug!("Executing transaction");
+    client.sync_state().await?;
+    let transaction_execution_result = client.new_transaction(tx_request)?;
     let transaction_id = transaction_execution_result.executed_transaction().id();
 
-    println!("Sending transaction to node");
-    client.submit_transaction(transaction_execution_result).await.unwrap();
-    println!("Transaction sent to node");
-
-    // wait until tx is committed
-    loop {
-        println!("Syncing State...");
-        client.sync_state().await.unwrap();
-
-        // Check if executed transaction got committed by the node
-        let uncommited_transactions =
-            client.get_transactions(TransactionFilter::Uncomitted).unwrap();
-        let is_tx_committed = uncommited_transactions
-            .iter()
-            .find(|uncommited_tx| uncommited_tx.id == transaction_id)
-            .is_none();
-
-        if is_tx_committed {
-            break;
+    log::debug!("Sending transaction {} to node", transaction_id.to_hex());
+    client.submit_transaction(transaction_execution_result).await?;
+    Ok(transaction_id)
+}
+
+async fn is_tx_committed<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    transaction_id: TransactionId
+) -> Result<bool, ClientError> {
+    log::debug!("Syncing state until transaction {} is committed", transaction_id.to_hex());
+    client.sync_state().await?;
+
+    let uncommited_transactions = client.get_transactions(TransactionFilter::Uncomitted)?;
+    Ok(!uncommited_transactions.iter().any(|uncommited_tx| uncommited_tx.id == transaction_id))
+}
+
+// serializes a request built by `build_aze_unsigned_tx_request`, the transaction script is not part of it
+pub fn serialize_unsigned_tx_request(tx_request: &TransactionRequest) -> Vec<u8> {
+    let mut target = Vec::new();
+    tx_request.account_id().write_into(&mut target);
+    let output_notes = tx_request.expected_output_notes();
+    target.write_u16(output_notes.len() as u16);
+    target.write_many(output_notes);
+    target
+}
+
+pub fn deserialize_unsigned_tx_request(bytes: &[u8]) -> Result<TransactionRequest, DeserializationError> {
+    let mut source = SliceReader::new(bytes);
+    let account_id = AccountId::read_from(&mut source)?;
+    let notes_count = source.read_u16()? as usize;
+    let output_notes = source.read_many::<Note>(notes_count)?;
+    Ok(TransactionRequest::new(account_id, Default::default(), output_notes, None))
+}
+
+// executes a signed request against the local store and proves it without submitting it, the
+// execution result is handed back so it can be recorded once the transaction is relayed
+pub fn prove_tx_request(
+    client: &mut AzeClient,
+    tx_request: TransactionRequest
+) -> Result<(ProvenTransaction, TransactionResult), ClientError> {
+    log::debug!("Executing transaction");
+    let transaction_execution_result = client.new_transaction(tx_request)?;
+
+    log::debug!("Proving transaction");
+    let prover = TransactionProver::new(ProvingOptions::default());
+    let proven_transaction = prover
+        .prove_transaction(transaction_execution_result.executed_transaction().clone())
+        .map_err(ClientError::TransactionProvingError)?;
+    Ok((proven_transaction, transaction_execution_result))
+}
+
+// applies a transaction submitted by someone else to the store of `client_config`, so the account
+// moves on to the state it left and the transaction gets committed on a later sync
+pub fn record_relayed_transaction(
+    client_config: &ClientConfig,
+    transaction_result: TransactionResult
+) -> Result<(), ClientError> {
+    let store = SqliteStore::new(client_config.into())?;
+    store.apply_transaction(transaction_result).map_err(ClientError::StoreError)
+}
+
+// relays a transaction proven elsewhere and waits until the note it is expected to create gets committed,
+// returns false if that does not happen in time. The client is only locked while it syncs, so it keeps
+// serving other requests between attempts
+pub async fn submit_proven_tx_and_sync(
+    client: &Mutex<AzeClient>,
+    proven_transaction: ProvenTransaction,
+    expected_note_id: NoteId
+) -> Result<bool, ClientError> {
+    log::debug!("Sending proven transaction {} to node", proven_transaction.id().to_hex());
+    client.lock().await.rpc_api().submit_proven_transaction(proven_transaction).await?;
+
+    for _ in 0..TX_COMMIT_ATTEMPTS {
+        {
+            let mut client = client.lock().await;
+            log::debug!("Syncing state until note {} is committed", expected_note_id.to_hex());
+            client.sync_state().await?;
+
+            let is_note_committed = client
+                .get_input_notes(NoteFilter::Committed)?
+                .iter()
+                .any(|note| note.id() == expected_note_id);
+
+            if is_note_committed {
+                return Ok(true);
+            }
         }
 
-        std::thread::sleep(std::time::Duration::new(3, 0));
+        sleep(TX_COMMIT_INTERVAL).await;
     }
-}
\ No newline at end of file
+
+    Ok(false)
+}
@@ -0,0 +1,306 @@
+use aze_types::actions::{ GameState, PokerAction };
+use miden_client::store::AuthInfo;
+use miden_lib::AuthScheme;
+use miden_objects::{
+    accounts::{ Account, AccountId, AccountType },
+    assembly::{ ModuleAst, ProgramAst },
+    assets::{ Asset, FungibleAsset },
+    crypto::{
+        dsa::rpo_falcon512::SecretKey,
+        hash::rpo::Rpo256,
+        merkle::{ LeafIndex, MmrPeaks, PartialMmr, SimpleSmt },
+    },
+    notes::{ Note, NoteId, NoteInclusionProof, NoteType, NOTE_TREE_DEPTH },
+    transaction::{ ChainMmr, InputNote, InputNotes, TransactionArgs, TransactionInputs },
+    BlockHeader,
+    Digest,
+    Felt,
+    Word,
+    ZERO,
+};
+use miden_tx::{ DataStore, DataStoreError, TransactionExecutor, TransactionExecutorError };
+use rand::Rng;
+
+use crate::accounts::{ account_id, create_basic_aze_game_account, AccountStorageType };
+use crate::constants::DEFAULT_AUTH_SCRIPT;
+use crate::contracts::{ procedure_root, upgrade_game_script, GAME_UPDATE_CURRENT_TURN };
+use crate::keystore::rotate_auth_key_script;
+use crate::notes::{
+    create_play_bet_note,
+    create_play_call_note,
+    create_play_check_note,
+    create_play_fold_note,
+    create_play_raise_note,
+};
+use crate::storage::{ read_game_state, GameStorageSlotData };
+use crate::rng::AzeRng;
+use crate::utils::get_random_coin;
+
+// the chips of an action are minted by this faucet, it never has to exist for the executor
+const CHIPS_FAUCET_ID: u64 = account_id(
+    AccountType::FungibleFaucet,
+    AccountStorageType::OffChain,
+    0x2ada_c0de_0000_0001
+);
+const PLAYER_ACCOUNT_ID: u64 = account_id(
+    AccountType::RegularAccountImmutableCode,
+    AccountStorageType::OffChain,
+    0x2ada_c0de_0000_0002
+);
+
+const UPDATE_CURRENT_TURN_SCRIPT: &str =
+    "
+    use.miden::contracts::auth::basic->auth_tx
+
+    begin
+        call.{update_current_turn}
+        call.auth_tx::auth_tx_rpo_falcon512
+    end
+";
+
+/// Game account driven straight through `TransactionExecutor`, with no client, store or node.
+///
+/// Each action is sent as a note committed in a block of its own. The game account consumes it
+/// in a transaction signed with the game key, and the resulting delta is applied to the account.
+/// Successive calls continue from the state left by the previous one.
+pub struct GameTestHarness {
+    game_account: Account,
+    game_account_seed: Word,
+    game_key: SecretKey,
+}
+
+impl GameTestHarness {
+    pub fn new(slot_data: GameStorageSlotData) -> Self {
+        Self::with_account_type(slot_data, AccountType::RegularAccountImmutableCode)
+    }
+
+    // game account that can go through `upgrade`
+    pub fn with_updatable_code(slot_data: GameStorageSlotData) -> Self {
+        Self::with_account_type(slot_data, AccountType::RegularAccountUpdatableCode)
+    }
+
+    fn with_account_type(slot_data: GameStorageSlotData, account_type: AccountType) -> Self {
+        let mut rng = AzeRng;
+        let game_key = SecretKey::with_rng(&mut rng);
+        let auth_scheme = AuthScheme::RpoFalcon512 { pub_key: game_key.public_key() };
+
+        let (game_account, game_account_seed) = create_basic_aze_game_account(
+            rng.gen(),
+            auth_scheme,
+            account_type,
+            miden_objects::accounts::AccountStorageType::OffChain,
+            slot_data
+        ).expect("game storage slot data is valid");
+
+        Self { game_account, game_account_seed, game_key }
+    }
+
+    pub fn game_account(&self) -> &Account {
+        &self.game_account
+    }
+
+    pub fn game_state(&self) -> GameState {
+        read_game_state(self.game_account.storage())
+    }
+
+    /// Consumes the note of `action` with the game account and returns the state it leaves.
+    ///
+    /// `chips` are carried by the note, and only bets and raises read them as the amount.
+    pub fn play(
+        &mut self,
+        action: PokerAction,
+        chips: u8
+    ) -> Result<GameState, TransactionExecutorError> {
+        let faucet_id = AccountId::try_from(CHIPS_FAUCET_ID).expect("valid faucet id");
+        let player_id = AccountId::try_from(PLAYER_ACCOUNT_ID).expect("valid player id");
+        let game_id = self.game_account.id();
+
+        let assets = vec![
+            Asset::Fungible(FungibleAsset::new(faucet_id, chips as u64).expect("valid chips"))
+        ];
+        let rng = get_random_coin();
+        let note = (match action {
+            PokerAction::Bet =>
+                create_play_bet_note(player_id, game_id, assets, NoteType::Public, rng, chips),
+            PokerAction::Raise =>
+                create_play_raise_note(player_id, game_id, assets, NoteType::Public, rng, chips),
+            PokerAction::Call => create_play_call_note(player_id, game_id, assets, NoteType::Public, rng),
+            PokerAction::Fold => create_play_fold_note(player_id, game_id, assets, NoteType::Public, rng),
+            PokerAction::Check =>
+                create_play_check_note(player_id, game_id, assets, NoteType::Public, rng),
+        }).expect("action note is valid");
+
+        self.execute(DEFAULT_AUTH_SCRIPT.to_string(), vec![note])
+    }
+
+    // runs `update_current_turn` of the game account on its own
+    pub fn update_current_turn(&mut self) -> Result<GameState, TransactionExecutorError> {
+        let update_current_turn = procedure_root(GAME_UPDATE_CURRENT_TURN).to_hex();
+        self.execute(
+            UPDATE_CURRENT_TURN_SCRIPT.replace("{update_current_turn}", &update_current_turn),
+            vec![]
+        )
+    }
+
+    /// Migrates the storage of the game account to the layout of this build and sets `new_code_root`
+    /// as its code, in the transaction `upgrade_game_account` sends.
+    ///
+    /// Code changes aren't carried by account deltas yet, so the account keeps its current code here.
+    pub fn upgrade(&mut self, new_code_root: Digest) -> Result<GameState, TransactionExecutorError> {
+        let tx_script = upgrade_game_script(self.game_account.storage(), new_code_root);
+        self.execute(tx_script, vec![])
+    }
+
+    /// Sets the public key of `new_key` as the auth key of the game account, in a transaction signed
+    /// with the current one. Later transactions are signed with `new_key`.
+    pub fn rotate_auth_key(&mut self, new_key: SecretKey) -> Result<GameState, TransactionExecutorError> {
+        let game_state = self.execute(rotate_auth_key_script(new_key.public_key()), vec![])?;
+        self.game_key = new_key;
+        Ok(game_state)
+    }
+
+    fn execute(
+        &mut self,
+        tx_script: String,
+        notes: Vec<Note>
+    ) -> Result<GameState, TransactionExecutorError> {
+        let game_id = self.game_account.id();
+        let note_ids: Vec<NoteId> = notes
+            .iter()
+            .map(|note| note.id())
+            .collect();
+
+        let data_store = MockDataStore::new(
+            self.game_account.clone(),
+            (self.game_account.nonce() == ZERO).then_some(self.game_account_seed),
+            notes
+        );
+        let block_ref = data_store.block_header.block_num();
+
+        let mut executor = TransactionExecutor::new(data_store);
+        executor.load_account(game_id)?;
+
+        let tx_script = ProgramAst::parse(&tx_script).expect("shipped MASM is well-formed");
+        let script_inputs = vec![AuthInfo::RpoFalcon512(self.game_key.clone()).into_advice_inputs()];
+        let tx_script = executor.compile_tx_script(tx_script, script_inputs, vec![])?;
+        let tx_args = TransactionArgs::with_tx_script(tx_script);
+
+        let executed_transaction = executor.execute_transaction(game_id, block_ref, &note_ids, tx_args)?;
+
+        self.game_account
+            .apply_delta(executed_transaction.account_delta())
+            .expect("executed delta applies to the account it was executed against");
+        Ok(self.game_state())
+    }
+}
+
+/// Chain of two blocks: the notes are committed in the first one and the second one is the
+/// reference block of the transaction.
+struct MockDataStore {
+    account: Account,
+    account_seed: Option<Word>,
+    block_header: BlockHeader,
+    chain_mmr: ChainMmr,
+    notes: Vec<InputNote>,
+}
+
+impl MockDataStore {
+    fn new(account: Account, account_seed: Option<Word>, notes: Vec<Note>) -> Self {
+        let note_tree = SimpleSmt::<NOTE_TREE_DEPTH>
+            ::with_leaves(
+                notes
+                    .iter()
+                    .enumerate()
+                    .map(|(index, note)| {
+                        let metadata: Word = (*note.metadata()).into();
+                        (index as u64, Rpo256::merge(&[note.id().inner(), metadata.into()]).into())
+                    })
+            )
+            .expect("notes fit in the note tree");
+        let notes_block = mock_block_header(0, Digest::default(), Digest::default(), note_tree.root());
+
+        let mut partial_mmr = PartialMmr::from_peaks(
+            MmrPeaks::new(0, vec![]).expect("empty mmr peaks are valid")
+        );
+        partial_mmr.add(notes_block.hash(), true);
+        let block_header = mock_block_header(
+            1,
+            notes_block.hash(),
+            partial_mmr.peaks().hash_peaks(),
+            Digest::default()
+        );
+        let chain_mmr = ChainMmr::new(partial_mmr, vec![notes_block]).expect(
+            "notes block is tracked by the partial mmr"
+        );
+
+        let notes = notes
+            .into_iter()
+            .enumerate()
+            .map(|(index, note)| {
+                let note_path = note_tree.open(&LeafIndex::new(index as u64).unwrap()).path;
+                let proof = NoteInclusionProof::new(
+                    notes_block.block_num(),
+                    notes_block.sub_hash(),
+                    notes_block.note_root(),
+                    index as u64,
+                    note_path
+                ).expect("note is in the note tree");
+                InputNote::new(note, proof)
+            })
+            .collect();
+
+        Self { account, account_seed, block_header, chain_mmr, notes }
+    }
+}
+
+impl DataStore for MockDataStore {
+    fn get_transaction_inputs(
+        &self,
+        account_id: AccountId,
+        _block_ref: u32,
+        notes: &[NoteId]
+    ) -> Result<TransactionInputs, DataStoreError> {
+        if account_id != self.account.id() {
+            return Err(DataStoreError::AccountNotFound(account_id));
+        }
+
+        let input_notes = self.notes
+            .iter()
+            .filter(|note| notes.contains(&note.id()))
+            .cloned()
+            .collect();
+        let input_notes = InputNotes::new(input_notes).map_err(
+            DataStoreError::InvalidTransactionInput
+        )?;
+
+        TransactionInputs::new(
+            self.account.clone(),
+            self.account_seed,
+            self.block_header,
+            self.chain_mmr.clone(),
+            input_notes
+        ).map_err(DataStoreError::InvalidTransactionInput)
+    }
+
+    fn get_account_code(&self, account_id: AccountId) -> Result<ModuleAst, DataStoreError> {
+        if account_id != self.account.id() {
+            return Err(DataStoreError::AccountNotFound(account_id));
+        }
+        Ok(self.account.code().module().clone())
+    }
+}
+
+fn mock_block_header(block_num: u32, prev_hash: Digest, chain_root: Digest, note_root: Digest) -> BlockHeader {
+    BlockHeader::new(
+        prev_hash,
+        block_num,
+        chain_root,
+        Digest::default(),
+        Digest::default(),
+        note_root,
+        Digest::default(),
+        Digest::default(),
+        Felt::from(0u8),
+        Felt::from(block_num)
+    )
+}
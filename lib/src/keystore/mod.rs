@@ -0,0 +1,269 @@
+use std::{ fmt, fs, io, path::PathBuf };
+
+use aes_gcm::{ aead::Aead, Aes256Gcm, KeyInit, Nonce };
+use argon2::Argon2;
+use miden_client::{
+    client::{ rpc::NodeRpcClient, transactions::transaction_request::TransactionRequest, Client },
+    errors::ClientError,
+    store::{ AuthInfo, Store },
+};
+use miden_objects::{
+    accounts::AccountId,
+    assembly::ProgramAst,
+    crypto::{ dsa::rpo_falcon512::{ PublicKey, SecretKey }, rand::FeltRng },
+    utils::{ Deserializable, Serializable },
+    Word,
+};
+use rand::Rng;
+
+use crate::client::{ prepare_word, AzeClient };
+use crate::contracts::{ procedure_root, GAME_ROTATE_AUTH_KEY };
+use crate::executor::{ execute_tx_and_sync, ExecutorError };
+
+const KEY_FILE_MAGIC: &[u8; 4] = b"AZEK";
+const KEY_FILE_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = KEY_FILE_MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+
+const ROTATE_AUTH_KEY_SCRIPT: &str =
+    "
+    begin
+        push.{new_pub_key}
+        call.{rotate_auth_key}
+    end
+";
+
+#[derive(Debug)]
+pub enum KeyStoreError {
+    Io(io::Error),
+    KeyNotFound(AccountId),
+    // wrong passphrase or tampered file, the two can't be told apart
+    DecryptionFailed,
+    MalformedKeyFile,
+    // the rotation transaction failed or was not committed
+    Transaction(ExecutorError),
+}
+
+impl fmt::Display for KeyStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyStoreError::Io(err) => write!(f, "key file io error: {err}"),
+            KeyStoreError::KeyNotFound(account_id) => write!(f, "no key stored for account {account_id}"),
+            KeyStoreError::DecryptionFailed => write!(f, "key file could not be decrypted"),
+            KeyStoreError::MalformedKeyFile => write!(f, "malformed key file"),
+            KeyStoreError::Transaction(err) => write!(f, "transaction error: {err}"),
+        }
+    }
+}
+
+impl From<io::Error> for KeyStoreError {
+    fn from(err: io::Error) -> Self {
+        KeyStoreError::Io(err)
+    }
+}
+
+impl From<ClientError> for KeyStoreError {
+    fn from(err: ClientError) -> Self {
+        KeyStoreError::Transaction(ExecutorError::Client(err))
+    }
+}
+
+impl From<ExecutorError> for KeyStoreError {
+    fn from(err: ExecutorError) -> Self {
+        KeyStoreError::Transaction(err)
+    }
+}
+
+/// Directory of Falcon secret keys encrypted at rest, one file per account.
+///
+/// Every file is sealed with AES-256-GCM under a key derived from the passphrase with Argon2id
+/// and its own random salt. A key file holds the account id along with the secret key, so the
+/// same format is used for the files exported to players.
+pub struct AzeKeyStore {
+    dir: PathBuf,
+    passphrase: String,
+}
+
+impl AzeKeyStore {
+    pub fn open(dir: impl Into<PathBuf>, passphrase: &str) -> Result<Self, KeyStoreError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, passphrase: passphrase.to_string() })
+    }
+
+    pub fn insert_key(&self, account_id: AccountId, key: &SecretKey) -> Result<(), KeyStoreError> {
+        let key_file = seal_key_file(&self.passphrase, account_id, key)?;
+        fs::write(self.key_path(account_id), key_file)?;
+        Ok(())
+    }
+
+    pub fn get_key(&self, account_id: AccountId) -> Result<SecretKey, KeyStoreError> {
+        let key_file = match fs::read(self.key_path(account_id)) {
+            Ok(key_file) => key_file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(KeyStoreError::KeyNotFound(account_id));
+            }
+            Err(err) => {
+                return Err(err.into());
+            }
+        };
+        let (stored_account_id, key) = open_key_file(&self.passphrase, &key_file)?;
+
+        // a file copied over another account's one is not accepted
+        if stored_account_id != account_id {
+            return Err(KeyStoreError::MalformedKeyFile);
+        }
+        Ok(key)
+    }
+
+    /// Re-seals the key of an account under `export_passphrase`, for the player to keep.
+    pub fn export_key(
+        &self,
+        account_id: AccountId,
+        export_passphrase: &str
+    ) -> Result<Vec<u8>, KeyStoreError> {
+        let key = self.get_key(account_id)?;
+        seal_key_file(export_passphrase, account_id, &key)
+    }
+
+    /// Stores a key file produced by `export_key` and returns the account it belongs to.
+    pub fn import_key(
+        &self,
+        key_file: &[u8],
+        export_passphrase: &str
+    ) -> Result<AccountId, KeyStoreError> {
+        let (account_id, key) = open_key_file(export_passphrase, key_file)?;
+        self.insert_key(account_id, &key)?;
+        Ok(account_id)
+    }
+
+    /// Key `account_id` signs its transactions with.
+    ///
+    /// Accounts created before the keystore existed have their key in the client store only, so
+    /// that one is used when the keystore has none.
+    pub fn account_auth<N: NodeRpcClient, R: FeltRng, S: Store>(
+        &self,
+        client: &Client<N, R, S>,
+        account_id: AccountId
+    ) -> Result<AuthInfo, KeyStoreError> {
+        match self.get_key(account_id) {
+            Ok(key) => Ok(AuthInfo::RpoFalcon512(key)),
+            Err(KeyStoreError::KeyNotFound(_)) => Ok(client.get_account_auth(account_id)?),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn key_path(&self, account_id: AccountId) -> PathBuf {
+        self.dir.join(format!("{account_id}.key"))
+    }
+}
+
+/// Replaces the auth key of a game account with a fresh one and returns its public key.
+///
+/// The rotation transaction is signed with the current key, see [AzeKeyStore::account_auth]. The
+/// new key is written next to the current one first and only takes its place once the transaction
+/// is committed, so a failed rotation leaves the account signing with the key it had.
+pub async fn rotate_game_key(
+    client: &mut AzeClient,
+    keystore: &AzeKeyStore,
+    game_account_id: AccountId
+) -> Result<PublicKey, KeyStoreError> {
+    let current_auth = keystore.account_auth(client, game_account_id)?;
+
+    let new_key = SecretKey::with_rng(&mut rand::thread_rng());
+    let new_pub_key = new_key.public_key();
+
+    let pending_path = keystore.key_path(game_account_id).with_extension("key.next");
+    fs::write(&pending_path, seal_key_file(&keystore.passphrase, game_account_id, &new_key)?)?;
+
+    let tx_script = ProgramAst::parse(&rotate_auth_key_script(new_pub_key)).expect(
+        "shipped MASM is well-formed"
+    );
+    let tx_script = {
+        let script_inputs = vec![current_auth.into_advice_inputs()];
+        client.compile_tx_script(tx_script, script_inputs, vec![])?
+    };
+    let tx_request = TransactionRequest::new(
+        game_account_id,
+        Default::default(),
+        vec![],
+        Some(tx_script)
+    );
+    execute_tx_and_sync(client, tx_request).await?;
+
+    fs::rename(&pending_path, keystore.key_path(game_account_id))?;
+    Ok(new_pub_key)
+}
+
+// tx script setting `new_pub_key` as the auth key of a game account, signed with its current key
+pub(crate) fn rotate_auth_key_script(new_pub_key: PublicKey) -> String {
+    let new_pub_key: Word = new_pub_key.into();
+    ROTATE_AUTH_KEY_SCRIPT.replace("{new_pub_key}", &prepare_word(&new_pub_key)).replace(
+        "{rotate_auth_key}",
+        &procedure_root(GAME_ROTATE_AUTH_KEY).to_hex()
+    )
+}
+
+// [magic | version | salt | nonce | ciphertext of (account id | secret key)]
+fn seal_key_file(
+    passphrase: &str,
+    account_id: AccountId,
+    key: &SecretKey
+) -> Result<Vec<u8>, KeyStoreError> {
+    let mut rng = rand::thread_rng();
+    let salt: [u8; SALT_LEN] = rng.gen();
+    let nonce: [u8; NONCE_LEN] = rng.gen();
+
+    let mut plaintext = u64::from(account_id).to_le_bytes().to_vec();
+    plaintext.extend(key.to_bytes());
+
+    let ciphertext = cipher(passphrase, &salt)?
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| KeyStoreError::DecryptionFailed)?;
+
+    let mut key_file = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    key_file.extend_from_slice(KEY_FILE_MAGIC);
+    key_file.push(KEY_FILE_VERSION);
+    key_file.extend_from_slice(&salt);
+    key_file.extend_from_slice(&nonce);
+    key_file.extend(ciphertext);
+    Ok(key_file)
+}
+
+fn open_key_file(passphrase: &str, key_file: &[u8]) -> Result<(AccountId, SecretKey), KeyStoreError> {
+    if
+        key_file.len() < HEADER_LEN ||
+        &key_file[..KEY_FILE_MAGIC.len()] != KEY_FILE_MAGIC ||
+        key_file[KEY_FILE_MAGIC.len()] != KEY_FILE_VERSION
+    {
+        return Err(KeyStoreError::MalformedKeyFile);
+    }
+    let salt_start = KEY_FILE_MAGIC.len() + 1;
+    let nonce_start = salt_start + SALT_LEN;
+    let salt = &key_file[salt_start..nonce_start];
+    let nonce = &key_file[nonce_start..HEADER_LEN];
+
+    let plaintext = cipher(passphrase, salt)?
+        .decrypt(Nonce::from_slice(nonce), &key_file[HEADER_LEN..])
+        .map_err(|_| KeyStoreError::DecryptionFailed)?;
+    if plaintext.len() < 8 {
+        return Err(KeyStoreError::MalformedKeyFile);
+    }
+
+    let account_id = AccountId::try_from(
+        u64::from_le_bytes(plaintext[..8].try_into().expect("slice is 8 bytes long"))
+    ).map_err(|_| KeyStoreError::MalformedKeyFile)?;
+    let key = SecretKey::read_from_bytes(&plaintext[8..]).map_err(
+        |_| KeyStoreError::MalformedKeyFile
+    )?;
+    Ok((account_id, key))
+}
+
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<Aes256Gcm, KeyStoreError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|_| KeyStoreError::DecryptionFailed)?;
+    Ok(Aes256Gcm::new(&key.into()))
+}
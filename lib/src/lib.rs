@@ -1,8 +1,22 @@
 pub mod accounts;
+pub mod auth;
 pub mod notes;
 pub mod utils;
 pub mod constants;
+pub mod contracts;
 // pub mod tests;
 pub mod client;
 pub mod executor;
-pub mod storage;
\ No newline at end of file
+pub mod storage;
+pub mod keystore;
+pub mod transport;
+pub mod engine;
+pub mod table;
+pub mod bots;
+pub mod rng;
+#[cfg(feature = "testing")]
+pub mod mock;
+#[cfg(feature = "testing")]
+pub mod harness;
+#[cfg(feature = "testing")]
+pub mod simulator;
\ No newline at end of file
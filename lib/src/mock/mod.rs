@@ -0,0 +1,342 @@
+use std::env::temp_dir;
+use std::sync::{ Arc, Mutex };
+
+use miden_client::{
+    client::{
+        rpc::{
+            AccountDetails,
+            AccountUpdateSummary,
+            CommittedNote,
+            NodeRpcClient,
+            NoteDetails,
+            NoteInclusionDetails,
+            StateSyncInfo,
+        },
+        Client,
+    },
+    config::{ ClientConfig, RpcConfig, StoreConfig },
+    errors::NodeRpcClientError,
+    store::sqlite_store::SqliteStore,
+};
+use miden_objects::{
+    accounts::AccountId,
+    crypto::{
+        hash::rpo::Rpo256,
+        merkle::{ LeafIndex, MerklePath, Mmr, MmrDelta, MmrProof, SimpleSmt },
+        rand::RpoRandomCoin,
+    },
+    notes::{ NoteEnvelope, NoteId, NoteTag, Nullifier, NOTE_TREE_DEPTH },
+    transaction::ProvenTransaction,
+    BlockHeader,
+    Digest,
+    Felt,
+    Word,
+};
+use rand::Rng;
+
+use crate::utils::get_random_coin;
+
+// nullifiers are matched on their 16 most significant bits, same as the node does
+const NULLIFIER_PREFIX_SHIFT: u64 = 48;
+
+pub type MockAzeClient = Client<MockRpcApi, RpoRandomCoin, SqliteStore>;
+
+// client backed by a fresh in-memory chain and its own sqlite file, so tests don't share any state
+pub fn create_mock_aze_client() -> MockAzeClient {
+    create_mock_aze_client_with_chain(MockRpcApi::new())
+}
+
+// client for another participant of an existing chain, e.g. a second player at the table
+pub fn create_mock_aze_client_with_chain(rpc_api: MockRpcApi) -> MockAzeClient {
+    let mut store_path = temp_dir();
+    store_path.push(format!("aze-mock-{}.sqlite3", rand::thread_rng().gen::<u64>()));
+
+    let store_config = StoreConfig {
+        database_filepath: store_path.to_string_lossy().into_owned(),
+    };
+    let client_config = ClientConfig::new(store_config, RpcConfig::default());
+
+    let store = SqliteStore::new((&client_config).into()).unwrap();
+    let executor_store = SqliteStore::new((&client_config).into()).unwrap();
+    MockAzeClient::new(rpc_api, get_random_coin(), store, executor_store, true)
+}
+
+struct MockBlock {
+    header: BlockHeader,
+    notes: Vec<NoteEnvelope>,
+    note_tree: SimpleSmt<NOTE_TREE_DEPTH>,
+    nullifiers: Vec<Nullifier>,
+    account_updates: Vec<(AccountId, Digest)>,
+}
+
+impl MockBlock {
+    fn note_path(&self, note_index: usize) -> MerklePath {
+        let leaf_index = LeafIndex::new(note_index as u64).expect("block notes fit in the note tree");
+        self.note_tree.open(&leaf_index).path
+    }
+
+    fn has_nullifier_with_prefix(&self, nullifier_prefixes: &[u16]) -> bool {
+        self.nullifiers.iter().any(|nullifier| nullifier_prefixes.contains(&nullifier_prefix(nullifier)))
+    }
+
+    fn has_note_with_tag(&self, note_tags: &[NoteTag]) -> bool {
+        self.notes.iter().any(|note| note_tags.contains(&note.metadata().tag()))
+    }
+}
+
+struct MockChain {
+    blocks: Vec<MockBlock>,
+    mmr: Mmr,
+}
+
+impl MockChain {
+    fn new() -> Self {
+        let mut chain = Self { blocks: vec![], mmr: Mmr::new() };
+        chain.seal_block(vec![], vec![], vec![]);
+        chain
+    }
+
+    fn chain_tip(&self) -> u32 {
+        (self.blocks.len() - 1) as u32
+    }
+
+    // every submitted transaction goes into a block of its own
+    fn seal_block(
+        &mut self,
+        notes: Vec<NoteEnvelope>,
+        nullifiers: Vec<Nullifier>,
+        account_updates: Vec<(AccountId, Digest)>
+    ) {
+        let block_num = self.blocks.len() as u32;
+
+        let note_tree = SimpleSmt::<NOTE_TREE_DEPTH>
+            ::with_leaves(
+                notes
+                    .iter()
+                    .enumerate()
+                    .map(|(index, note)| (index as u64, note_leaf(note)))
+            )
+            .expect("block notes fit in the note tree");
+
+        let prev_hash = self.blocks
+            .last()
+            .map(|block| block.header.hash())
+            .unwrap_or_default();
+        let header = BlockHeader::new(
+            prev_hash,
+            block_num,
+            self.mmr.accumulator().hash_peaks(),
+            Digest::default(),
+            Digest::default(),
+            note_tree.root(),
+            Digest::default(),
+            Digest::default(),
+            Felt::from(0u8),
+            Felt::from(block_num)
+        );
+
+        self.mmr.add(header.hash());
+        self.blocks.push(MockBlock { header, notes, note_tree, nullifiers, account_updates });
+    }
+}
+
+/// In-process stand-in for the node, holding a chain that only lives in memory.
+///
+/// Every proven transaction submitted to it is applied right away in a new block: its nullifiers
+/// are spent, its output notes are added to the block note tree and the final hash of its account
+/// is recorded, so clients sync against it exactly like against a node. Proofs are not verified.
+/// Clones share the same chain.
+#[derive(Clone)]
+pub struct MockRpcApi {
+    chain: Arc<Mutex<MockChain>>,
+}
+
+impl MockRpcApi {
+    pub fn new() -> Self {
+        Self { chain: Arc::new(Mutex::new(MockChain::new())) }
+    }
+}
+
+impl Default for MockRpcApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeRpcClient for MockRpcApi {
+    async fn submit_proven_transaction(
+        &mut self,
+        proven_transaction: ProvenTransaction
+    ) -> Result<(), NodeRpcClientError> {
+        let mut chain = self.chain.lock().unwrap();
+
+        let nullifiers: Vec<Nullifier> = proven_transaction.input_notes().iter().copied().collect();
+        let is_double_spend = chain.blocks
+            .iter()
+            .flat_map(|block| block.nullifiers.iter())
+            .any(|spent| nullifiers.contains(spent));
+        if is_double_spend {
+            return Err(
+                NodeRpcClientError::RequestError(
+                    "submit_proven_transaction".to_string(),
+                    "input note already consumed".to_string()
+                )
+            );
+        }
+
+        chain.seal_block(
+            proven_transaction.output_notes().iter().cloned().collect(),
+            nullifiers,
+            vec![(proven_transaction.account_id(), proven_transaction.account_update().final_state_hash())]
+        );
+        Ok(())
+    }
+
+    async fn get_block_header_by_number(
+        &mut self,
+        block_num: Option<u32>,
+        include_mmr_proof: bool
+    ) -> Result<(BlockHeader, Option<MmrProof>), NodeRpcClientError> {
+        let chain = self.chain.lock().unwrap();
+        let block_num = block_num.unwrap_or(chain.chain_tip());
+        let block = chain.blocks
+            .get(block_num as usize)
+            .ok_or(NodeRpcClientError::ExpectedFieldMissing(format!("block {block_num}")))?;
+
+        let mmr_proof = if include_mmr_proof {
+            Some(
+                chain.mmr
+                    .open(block_num as usize, chain.mmr.forest())
+                    .expect("every block is in the chain mmr")
+            )
+        } else {
+            None
+        };
+        Ok((block.header, mmr_proof))
+    }
+
+    // only the inclusion of a note is known, the transactions don't carry note details
+    async fn get_notes_by_id(
+        &mut self,
+        note_ids: &[NoteId]
+    ) -> Result<Vec<NoteDetails>, NodeRpcClientError> {
+        let chain = self.chain.lock().unwrap();
+
+        let mut notes = vec![];
+        for block in chain.blocks.iter() {
+            for (note_index, note) in block.notes.iter().enumerate() {
+                if !note_ids.contains(&note.note_id()) {
+                    continue;
+                }
+                let inclusion = NoteInclusionDetails::new(
+                    block.header.block_num(),
+                    note_index as u32,
+                    block.note_path(note_index)
+                );
+                notes.push(NoteDetails::OffChain(note.note_id(), *note.metadata(), inclusion));
+            }
+        }
+        Ok(notes)
+    }
+
+    async fn sync_state(
+        &mut self,
+        block_num: u32,
+        account_ids: &[AccountId],
+        note_tags: &[NoteTag],
+        nullifiers_tags: &[u16]
+    ) -> Result<StateSyncInfo, NodeRpcClientError> {
+        let chain = self.chain.lock().unwrap();
+        let chain_tip = chain.chain_tip();
+
+        // the response stops at the first block with something for the client, or at the tip
+        let response_block = chain.blocks
+            .iter()
+            .skip((block_num as usize) + 1)
+            .find(|block| block.has_note_with_tag(note_tags) || block.has_nullifier_with_prefix(nullifiers_tags))
+            .unwrap_or(&chain.blocks[chain_tip as usize]);
+        let response_block_num = response_block.header.block_num();
+
+        let account_hash_updates = account_ids
+            .iter()
+            .filter_map(|account_id| {
+                chain.blocks[(block_num as usize) + 1..=response_block_num as usize]
+                    .iter()
+                    .flat_map(|block| block.account_updates.iter())
+                    .filter(|(updated_id, _)| updated_id == account_id)
+                    .last()
+                    .copied()
+            })
+            .collect();
+
+        let note_inclusions = response_block.notes
+            .iter()
+            .enumerate()
+            .filter(|(_, note)| note_tags.contains(&note.metadata().tag()))
+            .map(|(note_index, note)| {
+                CommittedNote::new(
+                    note.note_id(),
+                    note_index as u32,
+                    response_block.note_path(note_index),
+                    *note.metadata()
+                )
+            })
+            .collect();
+
+        let nullifiers = response_block.nullifiers
+            .iter()
+            .filter(|nullifier| nullifiers_tags.contains(&nullifier_prefix(nullifier)))
+            .map(|nullifier| nullifier.inner())
+            .collect();
+
+        // a client already at the tip has nothing to catch up on
+        let from_forest = (block_num as usize) + 1;
+        let to_forest = response_block_num as usize;
+        let mmr_delta = if from_forest < to_forest {
+            chain.mmr.get_delta(from_forest, to_forest).expect("forests are within the chain mmr")
+        } else {
+            MmrDelta { forest: to_forest, data: vec![] }
+        };
+
+        Ok(StateSyncInfo {
+            chain_tip,
+            block_header: response_block.header,
+            mmr_delta,
+            account_hash_updates,
+            note_inclusions,
+            nullifiers,
+        })
+    }
+
+    // accounts are all off-chain here, so only their latest hash is known
+    async fn get_account_update(
+        &mut self,
+        account_id: AccountId
+    ) -> Result<AccountDetails, NodeRpcClientError> {
+        let chain = self.chain.lock().unwrap();
+
+        chain.blocks
+            .iter()
+            .rev()
+            .find_map(|block| {
+                block.account_updates
+                    .iter()
+                    .find(|(updated_id, _)| *updated_id == account_id)
+                    .map(|(_, hash)| {
+                        let summary = AccountUpdateSummary::new(*hash, block.header.block_num());
+                        AccountDetails::OffChain(account_id, summary)
+                    })
+            })
+            .ok_or(NodeRpcClientError::ExpectedFieldMissing(format!("account {account_id}")))
+    }
+}
+
+// leaf of the block note tree, the note id merged with its metadata
+fn note_leaf(note: &NoteEnvelope) -> Word {
+    let metadata: Word = (*note.metadata()).into();
+    Rpo256::merge(&[note.note_id().inner(), metadata.into()]).into()
+}
+
+fn nullifier_prefix(nullifier: &Nullifier) -> u16 {
+    (nullifier.inner()[3].as_int() >> NULLIFIER_PREFIX_SHIFT) as u16
+}
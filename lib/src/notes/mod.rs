@@ -1,8 +1,8 @@
 use miden_lib::transaction::TransactionKernel;
 use miden_objects::{
-    accounts::{ Account, AccountCode, AccountId, AccountStorage, StorageSlotType },
-    assembly::{ ModuleAst, ProgramAst },
-    assets::{ Asset, AssetVault, FungibleAsset },
+    accounts::AccountId,
+    assembly::ProgramAst,
+    assets::{ Asset, FungibleAsset },
     crypto::rand::{ FeltRng, RpoRandomCoin },
     notes::{
         Note,
@@ -15,61 +15,126 @@ use miden_objects::{
         NoteTag,
         NoteType,
     },
-    transaction::{ TransactionArgs, InputNote },
+    transaction::InputNote,
+    utils::Deserializable,
     Felt,
     NoteError,
-    Word,
     ZERO,
 };
-use miden_tx::TransactionExecutor;
 use miden_client::{
     client::{
         rpc::NodeRpcClient,
         transactions::transaction_request::{ TransactionRequest, TransactionTemplate },
     },
+    errors::ClientError,
     store::Store,
 };
 use miden_client::client::Client;
 use crate::client::AzeClient;
+use crate::contracts::poker_library;
 use crate::executor::execute_tx_and_sync;
-use crate::constants::{ BUY_IN_AMOUNT, TRANSFER_AMOUNT };
-use std::rc::Rc;
+use crate::constants::BUY_IN_AMOUNT;
+use std::collections::BTreeMap;
+use std::sync::{ Mutex, OnceLock };
 
-pub fn create_send_card_note<R: FeltRng, N: NodeRpcClient, S: Store>(
-    client: &mut Client<N, R, S>,
+/// Tag shared by every note of a table, derived from the game account id.
+///
+/// Deal notes sent by the game and action notes sent to it all carry it, so a player client
+/// that registers it with `add_game_note_tag` picks them up on `sync_state`.
+pub fn game_note_tag(game_account_id: AccountId) -> Result<NoteTag, NoteError> {
+    NoteTag::from_account_id(game_account_id, NoteExecutionMode::Local)
+}
+
+pub fn add_game_note_tag(client: &mut AzeClient, game_account_id: AccountId) -> Result<(), ClientError> {
+    let tag = game_note_tag(game_account_id).map_err(ClientError::NoteError)?;
+
+    // registering a table twice is harmless
+    if client.get_note_tags()?.contains(&tag) {
+        return Ok(());
+    }
+    client.add_note_tag(tag)
+}
+
+/// Kinds of notes exchanged at a table, each with its own script.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GameNoteKind {
+    Deal,
+    Bet,
+    Raise,
+    Call,
+    Fold,
+    Check,
+}
+
+impl GameNoteKind {
+    // parsed script ASTs serialized by build.rs
+    fn masb(&self) -> &'static [u8] {
+        match self {
+            GameNoteKind::Deal => include_bytes!(concat!(env!("OUT_DIR"), "/assets/game_notes/deal.masb")),
+            GameNoteKind::Bet => include_bytes!(concat!(env!("OUT_DIR"), "/assets/game_notes/bet.masb")),
+            GameNoteKind::Raise => include_bytes!(concat!(env!("OUT_DIR"), "/assets/game_notes/raise.masb")),
+            GameNoteKind::Call => include_bytes!(concat!(env!("OUT_DIR"), "/assets/game_notes/call.masb")),
+            GameNoteKind::Fold => include_bytes!(concat!(env!("OUT_DIR"), "/assets/game_notes/fold.masb")),
+            GameNoteKind::Check => include_bytes!(concat!(env!("OUT_DIR"), "/assets/game_notes/check.masb")),
+        }
+    }
+}
+
+/// Compiled script of a kind of game note.
+///
+/// Each script is compiled the first time a note of its kind is built and reused afterwards.
+pub fn game_note_script(kind: GameNoteKind) -> NoteScript {
+    static NOTE_SCRIPTS: OnceLock<Mutex<BTreeMap<GameNoteKind, NoteScript>>> = OnceLock::new();
+
+    let mut note_scripts = NOTE_SCRIPTS.get_or_init(Default::default).lock().unwrap();
+    note_scripts
+        .entry(kind)
+        .or_insert_with(|| compile_game_note_script(kind))
+        .clone()
+}
+
+// compiles the script of `kind` from scratch, `game_note_script` is the cached way to get it
+pub fn compile_game_note_script(kind: GameNoteKind) -> NoteScript {
+    let script_ast = ProgramAst::read_from_bytes(kind.masb()).expect(
+        "game note scripts are serialized by build.rs"
+    );
+    // the poker library is linked so note scripts can use the contract procedures
+    let assembler = TransactionKernel::assembler()
+        .with_library(poker_library())
+        .expect("poker library links with the miden library");
+    let (note_script, _) = NoteScript::new(script_ast, &assembler).expect(
+        "shipped MASM is well-formed"
+    );
+    note_script
+}
+
+pub fn create_send_card_note(
     sender_account_id: AccountId,
-    target_account_id: AccountId,
     assets: Vec<Asset>,
-    note_type: NoteType,
     mut rng: RpoRandomCoin,
     cards: [[Felt; 4]; 2]
 ) -> Result<Note, NoteError> {
-    let note_script = include_str!("../../contracts/notes/game/deal.masm");
-    // TODO: hide it under feature flag debug (.with_debug_mode(true))
-    let script_ast = ProgramAst::parse(note_script).unwrap();
-    let note_script = client.compile_note_script(script_ast, vec![]).unwrap();
+    let note_script = game_note_script(GameNoteKind::Deal);
 
     let card_1 = cards[0];
     let card_2 = cards[1];
 
-    let mut inputs = [card_1.as_slice(), card_2.as_slice()].concat();
-    println!("card Inputs: {:?}", inputs);
-
+    let inputs = [card_1.as_slice(), card_2.as_slice()].concat();
     let note_inputs = NoteInputs::new(inputs).unwrap();
-    let tag = NoteTag::from_account_id(target_account_id, NoteExecutionMode::Local)?;
+    // the deal is tagged for the table rather than the player, like every other game note
+    let tag = game_note_tag(sender_account_id)?;
     let serial_num = rng.draw_word();
     let aux = ZERO;
 
-    // TODO: For now hardcoding notes to be public, + Also find out what encrypted notes means
-    let metadata = NoteMetadata::new(sender_account_id, NoteType::Public, tag, aux)?;
+    // hole cards are never published, the note reaches the player through the server inbox
+    let metadata = NoteMetadata::new(sender_account_id, NoteType::OffChain, tag, aux)?;
     let vault = NoteAssets::new(assets)?;
     let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
 
     Ok(Note::new(vault, metadata, recipient))
 }
 
-pub fn create_play_bet_note<R: FeltRng, N: NodeRpcClient, S: Store>(
-    client: &mut Client<N, R, S>,
+pub fn create_play_bet_note(
     sender_account_id: AccountId,
     target_account_id: AccountId,
     assets: Vec<Asset>,
@@ -77,25 +142,22 @@ pub fn create_play_bet_note<R: FeltRng, N: NodeRpcClient, S: Store>(
     mut rng: RpoRandomCoin,
     player_bet: u8
 ) -> Result<Note, NoteError> {
-    let note_script = include_str!("../../contracts/notes/game/bet.masm");
-    let script_ast = ProgramAst::parse(note_script).unwrap();
-    let note_script = client.compile_note_script(script_ast, vec![]).unwrap();
+    let note_script = game_note_script(GameNoteKind::Bet);
 
     let inputs = vec![Felt::from(player_bet)];
     let note_inputs = NoteInputs::new(inputs).unwrap();
-    let tag = NoteTag::from_account_id(target_account_id, NoteExecutionMode::Local)?;
+    let tag = game_note_tag(target_account_id)?;
     let serial_num = rng.draw_word();
     let aux = ZERO;
 
-    let metadata = NoteMetadata::new(sender_account_id, NoteType::Public, tag, aux)?;
+    let metadata = NoteMetadata::new(sender_account_id, note_type, tag, aux)?;
     let vault = NoteAssets::new(assets)?;
     let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
 
     Ok(Note::new(vault, metadata, recipient))
 }
 
-pub fn create_play_raise_note<R: FeltRng, N: NodeRpcClient, S: Store>(
-    client: &mut Client<N, R, S>,
+pub fn create_play_raise_note(
     sender_account_id: AccountId,
     target_account_id: AccountId,
     assets: Vec<Asset>,
@@ -103,89 +165,78 @@ pub fn create_play_raise_note<R: FeltRng, N: NodeRpcClient, S: Store>(
     mut rng: RpoRandomCoin,
     player_bet: u8
 ) -> Result<Note, NoteError> {
-    let note_script = include_str!("../../contracts/notes/game/raise.masm");
-    let script_ast = ProgramAst::parse(note_script).unwrap();
-    let note_script = client.compile_note_script(script_ast, vec![]).unwrap();
+    let note_script = game_note_script(GameNoteKind::Raise);
 
     let inputs = vec![Felt::from(player_bet)];
     let note_inputs = NoteInputs::new(inputs).unwrap();
-    let tag = NoteTag::from_account_id(target_account_id, NoteExecutionMode::Local)?;
+    let tag = game_note_tag(target_account_id)?;
     let serial_num = rng.draw_word();
     let aux = ZERO;
 
-    let metadata = NoteMetadata::new(sender_account_id, NoteType::Public, tag, aux)?;
+    let metadata = NoteMetadata::new(sender_account_id, note_type, tag, aux)?;
     let vault = NoteAssets::new(assets)?;
     let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
 
     Ok(Note::new(vault, metadata, recipient))
 }
 
-pub fn create_play_call_note<R: FeltRng, N: NodeRpcClient, S: Store>(
-    client: &mut Client<N, R, S>,
+pub fn create_play_call_note(
     sender_account_id: AccountId,
     target_account_id: AccountId,
     assets: Vec<Asset>,
     note_type: NoteType,
     mut rng: RpoRandomCoin
 ) -> Result<Note, NoteError> {
-    let note_script = include_str!("../../contracts/notes/game/call.masm");
-    let script_ast = ProgramAst::parse(note_script).unwrap();
-    let note_script = client.compile_note_script(script_ast, vec![]).unwrap();
+    let note_script = game_note_script(GameNoteKind::Call);
 
     let note_inputs = NoteInputs::new(vec![]).unwrap();
-    let tag = NoteTag::from_account_id(target_account_id, NoteExecutionMode::Local)?;
+    let tag = game_note_tag(target_account_id)?;
     let serial_num = rng.draw_word();
     let aux = ZERO;
 
-    let metadata = NoteMetadata::new(sender_account_id, NoteType::Public, tag, aux)?;
+    let metadata = NoteMetadata::new(sender_account_id, note_type, tag, aux)?;
     let vault = NoteAssets::new(assets)?;
     let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
 
     Ok(Note::new(vault, metadata, recipient))
 }
 
-pub fn create_play_fold_note<R: FeltRng, N: NodeRpcClient, S: Store>(
-    client: &mut Client<N, R, S>,
+pub fn create_play_fold_note(
     sender_account_id: AccountId,
     target_account_id: AccountId,
     assets: Vec<Asset>,
     note_type: NoteType,
     mut rng: RpoRandomCoin
 ) -> Result<Note, NoteError> {
-    let note_script = include_str!("../../contracts/notes/game/fold.masm");
-    let script_ast = ProgramAst::parse(note_script).unwrap();
-    let note_script = client.compile_note_script(script_ast, vec![]).unwrap();
+    let note_script = game_note_script(GameNoteKind::Fold);
 
     let note_inputs = NoteInputs::new(vec![]).unwrap();
-    let tag = NoteTag::from_account_id(target_account_id, NoteExecutionMode::Local)?;
+    let tag = game_note_tag(target_account_id)?;
     let serial_num = rng.draw_word();
     let aux = ZERO;
 
-    let metadata = NoteMetadata::new(sender_account_id, NoteType::Public, tag, aux)?;
+    let metadata = NoteMetadata::new(sender_account_id, note_type, tag, aux)?;
     let vault = NoteAssets::new(assets)?;
     let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
 
     Ok(Note::new(vault, metadata, recipient))
 }
 
-pub fn create_play_check_note<R: FeltRng, N: NodeRpcClient, S: Store>(
-    client: &mut Client<N, R, S>,
+pub fn create_play_check_note(
     sender_account_id: AccountId,
     target_account_id: AccountId,
     assets: Vec<Asset>,
     note_type: NoteType,
     mut rng: RpoRandomCoin
 ) -> Result<Note, NoteError> {
-    let note_script = include_str!("../../contracts/notes/game/check.masm");
-    let script_ast = ProgramAst::parse(note_script).unwrap();
-    let note_script = client.compile_note_script(script_ast, vec![]).unwrap();
+    let note_script = game_note_script(GameNoteKind::Check);
 
     let note_inputs = NoteInputs::new(vec![]).unwrap();
-    let tag = NoteTag::from_account_id(target_account_id, NoteExecutionMode::Local)?;
+    let tag = game_note_tag(target_account_id)?;
     let serial_num = rng.draw_word();
     let aux = ZERO;
 
-    let metadata = NoteMetadata::new(sender_account_id, NoteType::Public, tag, aux)?;
+    let metadata = NoteMetadata::new(sender_account_id, note_type, tag, aux)?;
     let vault = NoteAssets::new(assets)?;
     let recipient = NoteRecipient::new(serial_num, note_script, note_inputs);
 
@@ -193,8 +244,8 @@ pub fn create_play_check_note<R: FeltRng, N: NodeRpcClient, S: Store>(
 }
 
 // TODO: remove this function after testing
-pub async fn mint_note(
-    client: &mut AzeClient,
+pub async fn mint_note<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
     basic_account_id: AccountId,
     faucet_account_id: AccountId,
     note_type: NoteType
@@ -209,19 +260,19 @@ pub async fn mint_note(
         note_type
     );
 
-    println!("Minting Asset");
+    log::debug!("Minting asset");
     let tx_request = client.build_transaction_request(tx_template).unwrap();
-    let _ = execute_tx_and_sync(client, tx_request.clone()).await;
+    execute_tx_and_sync(client, tx_request.clone()).await.unwrap();
 
     // Check that note is committed and return it
-    println!("Fetching Committed Notes...");
+    log::debug!("Fetching committed notes");
     let note_id = tx_request.expected_output_notes()[0].id();
     let note = client.get_input_note(note_id).unwrap();
     note.try_into().unwrap()
 }
 // TODO: remove it after testing the flow
-pub async fn consume_notes(
-    client: &mut AzeClient,
+pub async fn consume_notes<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
     account_id: AccountId,
     input_notes: &[InputNote]
 ) {
@@ -232,7 +283,7 @@ pub async fn consume_notes(
             .map(|n| n.id())
             .collect()
     );
-    println!("Consuming Note...");
+    log::debug!("Consuming notes");
     let tx_request: TransactionRequest = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(client, tx_request).await;
+    execute_tx_and_sync(client, tx_request).await.unwrap();
 }
@@ -0,0 +1,78 @@
+use std::{ cell::RefCell, marker::PhantomData };
+
+use rand::{ rngs::StdRng, RngCore, SeedableRng };
+
+thread_local! {
+    // set while a simulation runs on this thread
+    static SIMULATION_RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Randomness behind every value aze draws: keys, account seeds, note serial numbers, the coin
+/// of new clients and the deck shuffle.
+///
+/// Values come from the thread rng, unless a `Simulation` is running on the current thread, in
+/// which case they all follow from its master seed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AzeRng;
+
+impl RngCore for AzeRng {
+    fn next_u32(&mut self) -> u32 {
+        draw(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        draw(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        draw(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        draw(|rng| rng.try_fill_bytes(dest))
+    }
+}
+
+fn draw<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    SIMULATION_RNG.with(|simulation_rng| {
+        match simulation_rng.borrow_mut().as_mut() {
+            Some(rng) => f(rng),
+            None => f(&mut rand::thread_rng()),
+        }
+    })
+}
+
+/// Seeds `AzeRng` on the current thread for as long as it is alive.
+///
+/// A client created while the simulation runs gets its coin from the master seed as well, so the
+/// accounts, hands, transactions and note ids it produces are the same on every run with that
+/// seed, and recording the seed is enough to replay them. Falcon signatures are the exception,
+/// they carry a nonce of their own, but they don't end up in any id.
+///
+/// The seed is kept per thread, so the simulation is not `Send` and has to be driven from a
+/// current-thread runtime, as `#[tokio::test]` uses by default.
+pub struct Simulation {
+    master_seed: u64,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Simulation {
+    pub fn start(master_seed: u64) -> Self {
+        SIMULATION_RNG.with(|simulation_rng| {
+            *simulation_rng.borrow_mut() = Some(StdRng::seed_from_u64(master_seed));
+        });
+        Self { master_seed, _not_send: PhantomData }
+    }
+
+    pub fn master_seed(&self) -> u64 {
+        self.master_seed
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        SIMULATION_RNG.with(|simulation_rng| {
+            *simulation_rng.borrow_mut() = None;
+        });
+    }
+}
@@ -0,0 +1,136 @@
+use aze_types::actions::GameState;
+use miden_client::{
+    client::{
+        accounts::{ AccountStorageMode, AccountTemplate },
+        transactions::transaction_request::TransactionTemplate,
+    },
+    store::TransactionFilter,
+};
+use miden_objects::{
+    accounts::AccountId,
+    assets::TokenSymbol,
+    notes::NoteId,
+    transaction::TransactionId,
+};
+use rand::Rng;
+
+use crate::bots::{ run_bots, Bot, Strategy };
+use crate::client::{ AzeAccountTemplate, AzeGameMethods };
+use crate::constants::{
+    BUY_IN_AMOUNT,
+    FIRST_PLAYER_INDEX,
+    HIGHEST_BET,
+    PLAYER_INITIAL_BALANCE,
+    SMALL_BLIND_AMOUNT,
+};
+use crate::executor::execute_tx_and_sync;
+use crate::mock::create_mock_aze_client;
+use crate::rng::{ AzeRng, Simulation };
+use crate::storage::GameStorageSlotData;
+use crate::table::{ deal_hole_cards, TableError };
+
+/// Everything a simulated game produced, two runs with the same seed record the same values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulationRecord {
+    pub master_seed: u64,
+    pub game_account_id: AccountId,
+    pub player_account_ids: Vec<AccountId>,
+    pub deal_note_ids: Vec<NoteId>,
+    // every transaction of the game in the order it was executed
+    pub transaction_ids: Vec<TransactionId>,
+    pub final_state: GameState,
+}
+
+/// Plays a whole game on an in-memory chain with every value drawn from `master_seed`.
+///
+/// A table is opened with a shuffled deck and one seat per strategy, the hole cards are dealt
+/// and `run_bots` plays the hand with the strategies in seat order. Strategies keeping
+/// randomness of their own have to be seeded as well for the game to replay.
+pub async fn simulate_game(
+    master_seed: u64,
+    strategies: Vec<Box<dyn Strategy>>,
+    max_actions: usize
+) -> Result<SimulationRecord, TableError> {
+    let simulation = Simulation::start(master_seed);
+    let mut client = create_mock_aze_client();
+
+    let slot_data = GameStorageSlotData::new(
+        SMALL_BLIND_AMOUNT,
+        BUY_IN_AMOUNT as u8,
+        strategies.len() as u8,
+        FIRST_PLAYER_INDEX,
+        HIGHEST_BET,
+        PLAYER_INITIAL_BALANCE
+    ).with_shuffle_seed(AzeRng.gen());
+    let (game_account, _) = client.new_game_account(
+        AzeAccountTemplate::GameAccount {
+            mutable_code: false,
+            storage_mode: AccountStorageMode::Local,
+        },
+        Some(slot_data)
+    )?;
+    let game_account_id = game_account.id();
+
+    let (faucet_account, _) = client.new_account(AccountTemplate::FungibleFaucet {
+        token_symbol: TokenSymbol::new("MATIC").expect("valid token symbol"),
+        decimals: 8,
+        max_supply: 1_000_000_000,
+        storage_mode: AccountStorageMode::Local,
+    })?;
+    let faucet_account_id = faucet_account.id();
+
+    let mut player_account_ids = vec![];
+    for _ in 0..strategies.len() {
+        let (player_account, _) = client.new_game_account(
+            AzeAccountTemplate::PlayerAccount {
+                mutable_code: false,
+                storage_mode: AccountStorageMode::Local,
+            },
+            None
+        )?;
+        player_account_ids.push(player_account.id());
+    }
+
+    let deal_notes = deal_hole_cards(
+        &mut client,
+        game_account_id,
+        &player_account_ids,
+        faucet_account_id,
+        BUY_IN_AMOUNT
+    ).await?;
+    let mut deal_note_ids = vec![];
+    for (player_account_id, deal_note) in player_account_ids.iter().zip(deal_notes) {
+        let tx_template = TransactionTemplate::ConsumeNotes(*player_account_id, vec![deal_note.id()]);
+        let tx_request = client.build_transaction_request(tx_template)?;
+        execute_tx_and_sync(&mut client, tx_request).await?;
+        deal_note_ids.push(deal_note.id());
+    }
+
+    let mut bots: Vec<Bot> = player_account_ids
+        .iter()
+        .zip(strategies)
+        .map(|(account_id, strategy)| Bot { account_id: *account_id, strategy })
+        .collect();
+    let final_state = run_bots(
+        &mut client,
+        game_account_id,
+        faucet_account_id,
+        &mut bots,
+        max_actions
+    ).await?;
+
+    let transaction_ids = client
+        .get_transactions(TransactionFilter::All)?
+        .iter()
+        .map(|transaction| transaction.id)
+        .collect();
+
+    Ok(SimulationRecord {
+        master_seed: simulation.master_seed(),
+        game_account_id,
+        player_account_ids,
+        deal_note_ids,
+        transaction_ids,
+        final_state,
+    })
+}
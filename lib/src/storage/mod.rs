@@ -1,3 +1,30 @@
+use std::ops::Range;
+
+use aze_types::actions::{ Card, GameState, PlayerState };
+use aze_types::events::GameEvent;
+use miden_objects::{ accounts::AccountStorage, Felt, FieldElement, Word };
+use crate::constants::{
+    BIG_BLIND_SLOT,
+    BUY_IN_SLOT,
+    CHECK_COUNTER_SLOT,
+    CONTRACT_VERSION_SLOT,
+    CURRENT_PHASE_SLOT,
+    CURRENT_TURN_INDEX_SLOT,
+    FIRST_PLAYER_INDEX,
+    FLOP_INDEX_SLOT,
+    GAME_STORAGE_VERSION,
+    HIGHEST_BET_SLOT,
+    IS_FOLD_OFFSET,
+    NO_OF_PLAYERS_SLOT,
+    PLAYER_BALANCE_OFFSET,
+    PLAYER_BET_OFFSET,
+    PLAYER_CARD1_SLOT,
+    PLAYER_CARD2_SLOT,
+    PLAYER_STATS_SLOTS,
+    RAISER_INDEX_SLOT,
+    SMALL_BLIND_SLOT,
+};
+
 #[derive(Clone)]
 pub struct GameStorageSlotData {
     small_blind_amt: u8,
@@ -6,6 +33,8 @@ pub struct GameStorageSlotData {
     current_turn_index: u8,
     highest_bet: u8,
     player_balance: u8,
+    // the deck is laid out in order unless a seed to shuffle it with is given
+    shuffle_seed: Option<[u8; 32]>,
 }
 
 impl GameStorageSlotData {
@@ -24,9 +53,15 @@ impl GameStorageSlotData {
             current_turn_index,
             highest_bet,
             player_balance,
+            shuffle_seed: None,
         }
     }
 
+    pub fn with_shuffle_seed(mut self, shuffle_seed: [u8; 32]) -> Self {
+        self.shuffle_seed = Some(shuffle_seed);
+        self
+    }
+
     pub fn small_blind_amt(&self) -> u8 {
         self.small_blind_amt
     }
@@ -54,4 +89,152 @@ impl GameStorageSlotData {
     pub fn player_balance(&self) -> u8 {
         self.player_balance
     }
+
+    pub fn shuffle_seed(&self) -> Option<[u8; 32]> {
+        self.shuffle_seed
+    }
+}
+
+// the game contract keeps every scalar in the first element of its slot
+fn slot_value(storage: &AccountStorage, index: u8) -> u8 {
+    let word: Word = storage.get_item(index).into();
+    word[0].as_int() as u8
+}
+
+fn slot_card(storage: &AccountStorage, index: u8) -> Card {
+    let word: Word = storage.get_item(index).into();
+    Card {
+        suit: word[0].as_int() as u8,
+        rank: word[1].as_int() as u8,
+    }
+}
+
+pub fn seat_from_player_index(player_index: u8) -> u8 {
+    player_index.saturating_sub(FIRST_PLAYER_INDEX) / PLAYER_STATS_SLOTS
+}
+
+pub fn player_index_from_seat(seat: u8) -> u8 {
+    FIRST_PLAYER_INDEX + seat * PLAYER_STATS_SLOTS
+}
+
+// decodes the table from the game account storage, community cards are only included once revealed
+pub fn read_game_state(storage: &AccountStorage) -> GameState {
+    let player_count = slot_value(storage, NO_OF_PLAYERS_SLOT);
+    let current_phase = slot_value(storage, CURRENT_PHASE_SLOT);
+
+    let players = (0..player_count)
+        .map(|seat| {
+            let player_index = player_index_from_seat(seat);
+            PlayerState {
+                seat,
+                bet: slot_value(storage, player_index + PLAYER_BET_OFFSET),
+                balance: slot_value(storage, player_index + PLAYER_BALANCE_OFFSET),
+                has_folded: slot_value(storage, player_index + IS_FOLD_OFFSET) == 1,
+            }
+        })
+        .collect();
+
+    let community_cards = revealed_card_slots(storage)
+        .map(|index| slot_card(storage, index))
+        .collect();
+
+    let raiser_index = slot_value(storage, RAISER_INDEX_SLOT);
+
+    GameState {
+        small_blind: slot_value(storage, SMALL_BLIND_SLOT),
+        big_blind: slot_value(storage, BIG_BLIND_SLOT),
+        buy_in: slot_value(storage, BUY_IN_SLOT),
+        player_count,
+        current_turn: seat_from_player_index(slot_value(storage, CURRENT_TURN_INDEX_SLOT)),
+        raiser: (raiser_index != 0).then(|| seat_from_player_index(raiser_index)),
+        highest_bet: slot_value(storage, HIGHEST_BET_SLOT),
+        current_phase,
+        check_count: slot_value(storage, CHECK_COUNTER_SLOT),
+        players,
+        community_cards,
+    }
+}
+
+// deck slots of the community cards revealed so far
+pub fn revealed_card_slots(storage: &AccountStorage) -> Range<u8> {
+    // flop is revealed in phase 1, turn in phase 2 and river from phase 3 onwards
+    let revealed_cards = match slot_value(storage, CURRENT_PHASE_SLOT) {
+        0 => 0,
+        1 => 3,
+        2 => 4,
+        _ => 5,
+    };
+    let flop_index = slot_value(storage, FLOP_INDEX_SLOT);
+    flop_index..flop_index + revealed_cards
+}
+
+// hole cards from the player account storage, empty card slots mean the player has not been dealt in yet
+pub fn read_hole_cards(player_storage: &AccountStorage) -> Vec<Card> {
+    [PLAYER_CARD1_SLOT, PLAYER_CARD2_SLOT]
+        .iter()
+        .map(|slot| slot_card(player_storage, *slot))
+        .filter(|card| card.suit != 0)
+        .collect()
+}
+
+pub fn game_storage_version(storage: &AccountStorage) -> u8 {
+    slot_value(storage, CONTRACT_VERSION_SLOT)
+}
+
+// slot writes that move a game storage to the layout of this build, applying each version step
+// in turn. steps read the storage as it was before the migration
+pub fn migrate_game_storage(storage: &AccountStorage) -> Vec<(u8, Word)> {
+    let from_version = game_storage_version(storage);
+    let mut writes = vec![];
+
+    for version in from_version..GAME_STORAGE_VERSION {
+        match version {
+            // 0 -> 1 only introduces the version slot
+            0 => {}
+            _ => unreachable!("no migration from storage version {version}"),
+        }
+    }
+
+    if from_version < GAME_STORAGE_VERSION {
+        writes.push((
+            CONTRACT_VERSION_SLOT,
+            [Felt::from(GAME_STORAGE_VERSION), Felt::ZERO, Felt::ZERO, Felt::ZERO],
+        ));
+    }
+
+    writes
+}
+
+// events that take the table from `old` to `new`, in the order a client should apply them
+pub fn diff_game_state(old: &GameState, new: &GameState) -> Vec<GameEvent> {
+    let mut events = vec![];
+
+    for (old_player, new_player) in old.players.iter().zip(new.players.iter()) {
+        if old_player.bet != new_player.bet || old_player.balance != new_player.balance {
+            events.push(GameEvent::BetPlaced {
+                seat: new_player.seat,
+                bet: new_player.bet,
+                balance: new_player.balance,
+            });
+        }
+        if !old_player.has_folded && new_player.has_folded {
+            events.push(GameEvent::PlayerFolded { seat: new_player.seat });
+        }
+    }
+
+    if old.current_phase != new.current_phase {
+        events.push(GameEvent::PhaseChanged { phase: new.current_phase });
+    }
+
+    if new.community_cards.len() > old.community_cards.len() {
+        events.push(GameEvent::CardsRevealed {
+            cards: new.community_cards[old.community_cards.len()..].to_vec(),
+        });
+    }
+
+    if old.current_turn != new.current_turn {
+        events.push(GameEvent::TurnChanged { seat: new.current_turn });
+    }
+
+    events
 }
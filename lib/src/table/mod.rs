@@ -0,0 +1,350 @@
+use std::fmt;
+
+use aze_types::actions::{ GameState, PokerAction };
+use miden_client::{
+    client::{
+        rpc::NodeRpcClient,
+        transactions::transaction_request::{ TransactionRequest, TransactionTemplate },
+        Client,
+    },
+    errors::ClientError,
+    store::{ AuthInfo, Store },
+};
+use miden_objects::{
+    accounts::AccountId,
+    assets::{ Asset, FungibleAsset },
+    crypto::{ dsa::rpo_falcon512::SecretKey, rand::FeltRng },
+    notes::{ Note, NoteType },
+    Word,
+};
+use tokio::sync::Mutex;
+
+use crate::client::{
+    AzeClient,
+    AzeGameMethods,
+    AzeTransactionTemplate,
+    PlayBetTransactionData,
+    PlayCallTransactionData,
+    PlayCheckTransactionData,
+    PlayFoldTransactionData,
+    PlayRaiseTransactionData,
+    SendCardTransactionData,
+};
+use crate::executor::{ execute_shared_tx_and_sync, execute_tx_and_sync, ExecutorError };
+use crate::storage::read_game_state;
+
+#[derive(Debug)]
+pub enum TableError {
+    // the action was sent by a seat that does not hold the turn
+    NotPlayersTurn,
+    // bet or raise without an amount, or an amount the faucet can't issue
+    InvalidAction,
+    Client(ClientError),
+    // the node did not commit one of the transactions in time
+    Timeout,
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::NotPlayersTurn => write!(f, "it is not the player's turn"),
+            TableError::InvalidAction => write!(f, "invalid action for the table"),
+            TableError::Client(err) => write!(f, "client error: {err}"),
+            TableError::Timeout => write!(f, "a transaction was not committed in time"),
+        }
+    }
+}
+
+impl From<ClientError> for TableError {
+    fn from(err: ClientError) -> Self {
+        TableError::Client(err)
+    }
+}
+
+impl From<ExecutorError> for TableError {
+    fn from(err: ExecutorError) -> Self {
+        match err {
+            ExecutorError::Client(err) => TableError::Client(err),
+            ExecutorError::Timeout(_) => TableError::Timeout,
+        }
+    }
+}
+
+/// Returns the transaction playing `action` for the player sitting in `seat`.
+///
+/// The contract acts on behalf of whoever holds the current turn, so the seat is checked against
+/// the table first. The note carries the chips the action puts into the pot: the amount for bets
+/// and raises, whatever is missing to the highest bet for a call, and nothing otherwise.
+pub fn action_transaction_template(
+    game_state: &GameState,
+    seat: u8,
+    player_account_id: AccountId,
+    game_account_id: AccountId,
+    faucet_account_id: AccountId,
+    action: PokerAction,
+    amount: Option<u8>
+) -> Result<AzeTransactionTemplate, TableError> {
+    if game_state.current_turn != seat {
+        return Err(TableError::NotPlayersTurn);
+    }
+    let player_bet = game_state.players
+        .get(seat as usize)
+        .map(|player| player.bet)
+        .ok_or(TableError::InvalidAction)?;
+
+    let chips = match action {
+        PokerAction::Bet | PokerAction::Raise => amount.ok_or(TableError::InvalidAction)?,
+        PokerAction::Call => game_state.highest_bet.saturating_sub(player_bet),
+        PokerAction::Fold | PokerAction::Check => 0,
+    };
+    let fungible_asset = FungibleAsset::new(faucet_account_id, chips as u64).map_err(
+        |_| TableError::InvalidAction
+    )?;
+    let asset = Asset::Fungible(fungible_asset);
+
+    Ok(match action {
+        PokerAction::Bet =>
+            AzeTransactionTemplate::PlayBet(
+                PlayBetTransactionData::new(asset, player_account_id, game_account_id, chips)
+            ),
+        PokerAction::Raise =>
+            AzeTransactionTemplate::PlayRaise(
+                PlayRaiseTransactionData::new(asset, player_account_id, game_account_id, chips)
+            ),
+        PokerAction::Call =>
+            AzeTransactionTemplate::PlayCall(
+                PlayCallTransactionData::new(asset, player_account_id, game_account_id)
+            ),
+        PokerAction::Fold =>
+            AzeTransactionTemplate::PlayFold(
+                PlayFoldTransactionData::new(asset, player_account_id, game_account_id)
+            ),
+        PokerAction::Check =>
+            AzeTransactionTemplate::PlayCheck(
+                PlayCheckTransactionData::new(asset, player_account_id, game_account_id)
+            ),
+    })
+}
+
+/// Sends every player the deal note with their two hole cards and returns the notes in seat order.
+///
+/// Each deal note carries `buy_in` chips, so the game account is funded from the faucet once per
+/// seat first. Consuming the notes is left to the caller, since only the holder of a player's key can.
+pub async fn deal_hole_cards<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    game_account_id: AccountId,
+    player_account_ids: &[AccountId],
+    faucet_account_id: AccountId,
+    buy_in: u64
+) -> Result<Vec<Note>, TableError> {
+    let AuthInfo::RpoFalcon512(game_key) = client.get_account_auth(game_account_id)?;
+    let hole_cards = read_dealt_cards(client, game_account_id, player_account_ids.len())?;
+    let fungible_asset = FungibleAsset::new(faucet_account_id, buy_in).map_err(
+        |_| TableError::InvalidAction
+    )?;
+
+    let mut deal_notes = vec![];
+
+    for (i, (target_account_id, cards)) in player_account_ids.iter().zip(hole_cards).enumerate() {
+        log::debug!("Dealing seat {i} to account {target_account_id}");
+
+        // the buy-in is minted into the game account, which passes it on with the cards
+        let tx_request = mint_buy_in_request(client, game_account_id, fungible_asset)?;
+        execute_tx_and_sync(client, tx_request.clone()).await?;
+
+        let note_id = tx_request.expected_output_notes()[0].id();
+        let tx_request = client.build_consume_notes_tx_request(
+            game_account_id,
+            vec![note_id],
+            AuthInfo::RpoFalcon512(game_key.clone())
+        )?;
+        execute_tx_and_sync(client, tx_request).await?;
+
+        let txn_request = send_cards_request(
+            client,
+            game_account_id,
+            *target_account_id,
+            fungible_asset,
+            &cards,
+            &game_key
+        )?;
+        execute_tx_and_sync(client, txn_request.clone()).await?;
+
+        deal_notes.push(txn_request.expected_output_notes()[0].clone());
+    }
+
+    Ok(deal_notes)
+}
+
+/// Same as [deal_hole_cards] for a client shared with other tasks, which is only locked while each
+/// transaction is built, submitted and synced rather than for the whole deal. The game account signs
+/// with `game_key` rather than with the key the client's store keeps for it.
+pub async fn deal_hole_cards_shared(
+    client: &Mutex<AzeClient>,
+    game_account_id: AccountId,
+    game_key: &SecretKey,
+    player_account_ids: &[AccountId],
+    faucet_account_id: AccountId,
+    buy_in: u64
+) -> Result<Vec<Note>, TableError> {
+    let hole_cards = read_dealt_cards(
+        &*client.lock().await,
+        game_account_id,
+        player_account_ids.len()
+    )?;
+    let fungible_asset = FungibleAsset::new(faucet_account_id, buy_in).map_err(
+        |_| TableError::InvalidAction
+    )?;
+
+    let mut deal_notes = vec![];
+
+    for (i, (target_account_id, cards)) in player_account_ids.iter().zip(hole_cards).enumerate() {
+        log::debug!("Dealing seat {i} to account {target_account_id}");
+
+        let tx_request = mint_buy_in_request(&mut *client.lock().await, game_account_id, fungible_asset)?;
+        execute_shared_tx_and_sync(client, tx_request.clone()).await?;
+
+        let note_id = tx_request.expected_output_notes()[0].id();
+        let tx_request = client
+            .lock().await
+            .build_consume_notes_tx_request(
+                game_account_id,
+                vec![note_id],
+                AuthInfo::RpoFalcon512(game_key.clone())
+            )?;
+        execute_shared_tx_and_sync(client, tx_request).await?;
+
+        let txn_request = send_cards_request(
+            &mut *client.lock().await,
+            game_account_id,
+            *target_account_id,
+            fungible_asset,
+            &cards,
+            game_key
+        )?;
+        execute_shared_tx_and_sync(client, txn_request.clone()).await?;
+
+        deal_notes.push(txn_request.expected_output_notes()[0].clone());
+    }
+
+    Ok(deal_notes)
+}
+
+// the two cards of each seat, in the slots the deck was shuffled into when the game account was created
+fn read_dealt_cards<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &Client<N, R, S>,
+    game_account_id: AccountId,
+    player_count: usize
+) -> Result<Vec<[Word; 2]>, TableError> {
+    let (game_account, _) = client.get_account(game_account_id)?;
+    let game_account_storage = game_account.storage();
+
+    Ok(
+        (0..player_count)
+            .map(|i| {
+                let card_1 = game_account_storage.get_item((2 * i + 1) as u8);
+                let card_2 = game_account_storage.get_item((2 * i + 2) as u8);
+                [card_1.into(), card_2.into()]
+            })
+            .collect()
+    )
+}
+
+fn mint_buy_in_request<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    game_account_id: AccountId,
+    fungible_asset: FungibleAsset
+) -> Result<TransactionRequest, TableError> {
+    let tx_template = TransactionTemplate::MintFungibleAsset(
+        fungible_asset,
+        game_account_id,
+        NoteType::Public
+    );
+    Ok(client.build_transaction_request(tx_template)?)
+}
+
+fn send_cards_request<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    game_account_id: AccountId,
+    target_account_id: AccountId,
+    fungible_asset: FungibleAsset,
+    cards: &[Word; 2],
+    game_key: &SecretKey
+) -> Result<TransactionRequest, TableError> {
+    let sendcard_txn_data = SendCardTransactionData::new(
+        Asset::Fungible(fungible_asset),
+        game_account_id,
+        target_account_id,
+        cards
+    );
+    let transaction_template = AzeTransactionTemplate::SendCard(sendcard_txn_data);
+    let unsigned_request = client.build_aze_unsigned_tx_request(transaction_template)?;
+    Ok(client.sign_aze_tx_request(unsigned_request, AuthInfo::RpoFalcon512(game_key.clone()))?)
+}
+
+/// Plays `action` for the player sitting in `seat` at a table hosted by this client.
+///
+/// The client holds both the player and the game account: the player sends the action note and
+/// the game account consumes it right after, both transactions executed and proven locally. This
+/// is how tables are played without a server. Returns the table once the action is applied.
+pub async fn play_action<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    game_account_id: AccountId,
+    player_account_id: AccountId,
+    seat: u8,
+    faucet_account_id: AccountId,
+    action: PokerAction,
+    amount: Option<u8>
+) -> Result<GameState, TableError> {
+    let AuthInfo::RpoFalcon512(player_key) = client.get_account_auth(player_account_id)?;
+    play_action_with_key(
+        client,
+        game_account_id,
+        player_account_id,
+        seat,
+        faucet_account_id,
+        action,
+        amount,
+        player_key
+    ).await
+}
+
+/// Same as [play_action], with the action note signed by `player_key` rather than by the key the
+/// client's store keeps for the player, for players whose keys are kept elsewhere.
+#[allow(clippy::too_many_arguments)]
+pub async fn play_action_with_key<N: NodeRpcClient, R: FeltRng, S: Store>(
+    client: &mut Client<N, R, S>,
+    game_account_id: AccountId,
+    player_account_id: AccountId,
+    seat: u8,
+    faucet_account_id: AccountId,
+    action: PokerAction,
+    amount: Option<u8>,
+    player_key: SecretKey
+) -> Result<GameState, TableError> {
+    let (game_account, _) = client.get_account(game_account_id)?;
+    let transaction_template = action_transaction_template(
+        &read_game_state(game_account.storage()),
+        seat,
+        player_account_id,
+        game_account_id,
+        faucet_account_id,
+        action,
+        amount
+    )?;
+
+    let unsigned_request = client.build_aze_unsigned_tx_request(transaction_template)?;
+    let txn_request = client.sign_aze_tx_request(
+        unsigned_request,
+        AuthInfo::RpoFalcon512(player_key)
+    )?;
+    execute_tx_and_sync(client, txn_request.clone()).await?;
+
+    let note_id = txn_request.expected_output_notes()[0].id();
+    let tx_template = TransactionTemplate::ConsumeNotes(game_account_id, vec![note_id]);
+    let tx_request = client.build_transaction_request(tx_template)?;
+    execute_tx_and_sync(client, tx_request).await?;
+
+    let (game_account, _) = client.get_account(game_account_id)?;
+    Ok(read_game_state(game_account.storage()))
+}
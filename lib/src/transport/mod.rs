@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+
+use aze_types::accounts::{ GameSnapshot, SnapshotSlot };
+use miden_client::{ client::rpc::{ AccountDetails, NodeRpcClient }, errors::ClientError, store::InputNoteRecord };
+use miden_objects::{
+    accounts::{ hash_account, Account, AccountId, AccountStorage, SlotItem, StorageSlot },
+    crypto::{ dsa::rpo_falcon512::{ PublicKey, SecretKey, Signature }, hash::rpo::Rpo256 },
+    notes::{ Note, NoteId, Nullifier },
+    utils::{ Deserializable, Serializable },
+    Digest,
+    Felt,
+    Word,
+};
+
+use crate::client::AzeClient;
+use crate::constants::{ FIRST_DECK_SLOT, LAST_DECK_SLOT };
+use crate::storage::revealed_card_slots;
+
+// the node filters nullifiers by their 16 high bits
+const NULLIFIER_PREFIX_SHIFT: u64 = 48;
+
+// the node only keeps the hash of a private note, so the note itself is handed to its recipient
+// through the inbox hosted by the server, hex encoded like the rest of the api
+
+pub fn encode_note(note: &Note) -> String {
+    hex::encode(note.to_bytes())
+}
+
+pub fn decode_note(note_hex: &str) -> Option<Note> {
+    let bytes = hex::decode(note_hex).ok()?;
+    Note::read_from_bytes(&bytes).ok()
+}
+
+// adds a note received through the inbox to the client store, it gets committed on a later sync
+pub fn import_private_note(client: &mut AzeClient, note: Note) -> Result<(), ClientError> {
+    client.import_input_note(InputNoteRecord::from(note))
+}
+
+// returns the notes consumed on chain after `block_num`, along with the block the node was synced up to.
+// an inbox only holds notes meant for others, so their nullifiers are looked up without the client store
+pub async fn fetch_consumed_notes(
+    client: &mut AzeClient,
+    notes: &[Note],
+    block_num: u32
+) -> Result<(Vec<NoteId>, u32), ClientError> {
+    let note_ids: BTreeMap<Digest, NoteId> = notes
+        .iter()
+        .map(|note| (note.nullifier().inner(), note.id()))
+        .collect();
+    let mut nullifier_prefixes: Vec<u16> = notes
+        .iter()
+        .map(|note| nullifier_prefix(&note.nullifier()))
+        .collect();
+    nullifier_prefixes.sort_unstable();
+    nullifier_prefixes.dedup();
+
+    let mut consumed = vec![];
+    let mut block_num = block_num;
+    loop {
+        // each response stops at the first block with a matching nullifier
+        let sync_info = client.rpc_api().sync_state(block_num, &[], &[], &nullifier_prefixes).await?;
+        consumed.extend(sync_info.nullifiers.iter().filter_map(|nullifier| note_ids.get(nullifier)));
+
+        block_num = sync_info.block_header.block_num();
+        if block_num >= sync_info.chain_tip {
+            return Ok((consumed, block_num));
+        }
+    }
+}
+
+fn nullifier_prefix(nullifier: &Nullifier) -> u16 {
+    (nullifier.inner()[3].as_int() >> NULLIFIER_PREFIX_SHIFT) as u16
+}
+
+// game accounts are off-chain as well, seated players are handed a signed snapshot of the account
+// after every action and check it against the commitment the node holds. storage paths would give
+// away the cards next to the slots they open, so the deck is left out, apart from the community
+// cards already revealed, and committed to on its own
+
+pub fn sign_game_snapshot(game_account: &Account, game_key: &SecretKey) -> GameSnapshot {
+    let storage = game_account.storage();
+    let revealed_slots = revealed_card_slots(storage);
+    let is_hidden = |index: u8| {
+        (FIRST_DECK_SLOT..=LAST_DECK_SLOT).contains(&index) && !revealed_slots.contains(&index)
+    };
+
+    let slots: Vec<(u8, Word)> = (0..=u8::MAX)
+        .filter(|index| !is_hidden(*index))
+        .map(|index| (index, Word::from(storage.get_item(index))))
+        .filter(|(_, value)| *value != Word::default())
+        .collect();
+    let hidden_values: Vec<Word> = (FIRST_DECK_SLOT..=LAST_DECK_SLOT)
+        .filter(|index| is_hidden(*index))
+        .map(|index| storage.get_item(index).into())
+        .collect();
+    let deck_commitment = Rpo256::hash_elements(hidden_values.concat().as_slice());
+
+    let message = snapshot_message(game_account.hash(), deck_commitment, &slots);
+    GameSnapshot {
+        game_id: game_account.id().into(),
+        nonce: game_account.nonce().as_int(),
+        vault_root: game_account.vault().commitment().to_hex(),
+        storage_root: storage.root().to_hex(),
+        code_root: game_account.code().root().to_hex(),
+        deck_commitment: deck_commitment.to_hex(),
+        slots: slots
+            .iter()
+            .map(|(index, value)| SnapshotSlot { index: *index, value: Digest::from(*value).to_hex() })
+            .collect(),
+        signature: hex::encode(game_key.sign(message).to_bytes()),
+    }
+}
+
+// returns the storage handed out by a snapshot whose account hashes to `commitment` and which is signed
+// by the key in its slot 0. slots left out of the snapshot read as empty
+pub fn verify_game_snapshot(snapshot: &GameSnapshot, commitment: Digest) -> Option<AccountStorage> {
+    let game_account_id = AccountId::try_from(snapshot.game_id).ok()?;
+    let account_hash = hash_account(
+        game_account_id,
+        Felt::try_from(snapshot.nonce).ok()?,
+        Digest::try_from(snapshot.vault_root.as_str()).ok()?,
+        Digest::try_from(snapshot.storage_root.as_str()).ok()?,
+        Digest::try_from(snapshot.code_root.as_str()).ok()?
+    );
+    if account_hash != commitment {
+        return None;
+    }
+
+    let slots = snapshot.slots
+        .iter()
+        .map(|slot| Some((slot.index, Word::from(Digest::try_from(slot.value.as_str()).ok()?))))
+        .collect::<Option<Vec<_>>>()?;
+    let deck_commitment = Digest::try_from(snapshot.deck_commitment.as_str()).ok()?;
+    let signature = Signature::read_from_bytes(&hex::decode(&snapshot.signature).ok()?).ok()?;
+
+    let (_, pub_key) = slots.iter().find(|(index, _)| *index == 0)?;
+    let message = snapshot_message(account_hash, deck_commitment, &slots);
+    if !PublicKey::new(*pub_key).verify(message, &signature) {
+        return None;
+    }
+
+    AccountStorage::new(
+        slots
+            .into_iter()
+            .map(|(index, value)| SlotItem { index, slot: StorageSlot::new_value(value) })
+            .collect()
+    ).ok()
+}
+
+// latest state commitment of an account as recorded by the node
+pub async fn fetch_account_commitment(
+    client: &mut AzeClient,
+    account_id: AccountId
+) -> Result<Digest, ClientError> {
+    let (AccountDetails::OffChain(_, update) | AccountDetails::Public(_, update)) = client
+        .rpc_api()
+        .get_account_update(account_id).await?;
+    Ok(update.hash)
+}
+
+// what the game key signs: the account commitment, the commitment to the hidden deck and the slots handed out
+fn snapshot_message(account_hash: Digest, deck_commitment: Digest, slots: &[(u8, Word)]) -> Word {
+    let mut elements: Vec<Felt> = vec![];
+    elements.extend(Word::from(account_hash));
+    elements.extend(Word::from(deck_commitment));
+    for (index, value) in slots {
+        elements.push(Felt::from(*index));
+        elements.extend(value);
+    }
+    Rpo256::hash_elements(&elements).into()
+}
@@ -1,167 +1,255 @@
-use aze_lib::accounts::create_basic_aze_player_account;
+use std::collections::BTreeSet;
+
 use aze_lib::client::{
-    self,
-    create_aze_client,
-    AzeAccountTemplate,
+    is_custodial,
     AzeClient,
     AzeGameMethods,
-    AzeTransactionTemplate,
-    SendCardTransactionData,
 };
-use aze_lib::constants::BUY_IN_AMOUNT;
-use aze_lib::notes::{ consume_notes, mint_note };
-use aze_lib::executor::execute_tx_and_sync;
+use aze_lib::keystore::AzeKeyStore;
+use aze_lib::constants::{ FIRST_PLAYER_INDEX, MAX_PLAYERS, MIN_PLAYERS };
+use aze_lib::executor::execute_shared_tx_and_sync;
+use aze_lib::rng::AzeRng;
 use aze_lib::storage::GameStorageSlotData;
+use aze_lib::table::deal_hole_cards_shared;
 
 use aze_types::accounts::{
     AccountCreationError,
-    AccountCreationResponse,
+    AzeStorageMode,
+    GameCreationRequest,
+    GameCreationResponse,
+    PlayerAccountCreationRequest,
     PlayerAccountCreationResponse,
 };
 use aze_lib::utils::log_account_status;
-use miden_lib::AuthScheme;
 use miden_objects::{
-    assets::TokenSymbol,
-    assets::{ Asset, FungibleAsset },
-    crypto::dsa::rpo_falcon512::{ PublicKey, SecretKey },
-    notes::NoteType,
+    accounts::AccountId,
+    crypto::{ dsa::rpo_falcon512::{ PublicKey, SecretKey }, hash::rpo::RpoDigest },
 };
 use miden_client::client::{
-    accounts::{ AccountTemplate, AccountStorageMode },
+    accounts::AccountStorageMode,
     transactions::transaction_request::TransactionTemplate,
 };
 
-use actix_web::{ get, web::Json };
-
-// TODO: pass account id of the players as request object in this game
-#[get("/v1/game/create-account")]
-pub async fn create_aze_game_account() -> Result<
-    Json<AccountCreationResponse>,
-    AccountCreationError
-> {
-    let mut client: AzeClient = create_aze_client();
-    let slot_data = GameStorageSlotData::new(0, 0, 0, 0, 0, 0);
-
-    // TODO: creating player just for testing purposes
-    let (player_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::PlayerAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            None
-        )
-        .unwrap();
-
-    let (faucet_account, _) = client
-        .new_account(AccountTemplate::FungibleFaucet {
-            token_symbol: TokenSymbol::new("MATIC").unwrap(),
-            decimals: 8,
-            max_supply: 1_000_000_000,
-            storage_mode: AccountStorageMode::Local,
-        })
-        .unwrap();
-
-    let faucet_account_id = faucet_account.id();
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-
-    // TODO: get the player account ids from the request object
-    let player_account_ids = vec![player_account.id()];
-
-    let (game_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::GameAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            Some(slot_data)
-        )
-        .unwrap();
-
-    let game_account_id = game_account.id();
-    let game_account_storage = game_account.storage();
-
-    println!("Account created: {:?}", game_account_id);
-
-    println!("First client consuming note");
-    let note = mint_note(&mut client, game_account_id, faucet_account_id, NoteType::Public).await;
-    println!("Minted note");
-    consume_notes(&mut client, game_account_id, &[note]).await;
-    println!("Player account consumed note");
-
-    let sender_account_id = game_account_id;
-
-    let mut cards = vec![];
+use actix_web::{ post, web::Bytes, web::Json, HttpRequest };
+use rand::Rng;
+use tokio::sync::Mutex;
+use crate::auth::{ authenticate, verify_table_consents };
+use crate::api::snapshots::store_game_snapshot;
+use crate::repository::AzeRepository;
+use crate::state::{
+    AzeTable,
+    SharedAzeClient,
+    SharedAzeKeyStore,
+    SharedAzeNonces,
+    SharedAzeRepository,
+    SharedAzeSnapshots,
+};
 
-    for i in 1..2 * player_account_ids.len() + 1 {
-        let slot_index = i;
-        let card = game_account_storage.get_item(slot_index as u8);
-        println!("Card from game storage {:?}", card);
-        cards.push(card.into());
+#[post("/v1/game")]
+pub async fn create_aze_game(
+    client: SharedAzeClient,
+    keystore: SharedAzeKeyStore,
+    nonces: SharedAzeNonces,
+    repository: SharedAzeRepository,
+    snapshots: SharedAzeSnapshots,
+    req: HttpRequest,
+    body: Bytes
+) -> Result<Json<GameCreationResponse>, AccountCreationError> {
+    // the body is parsed by hand since the signature covers its raw bytes
+    let request: GameCreationRequest = serde_json
+        ::from_slice(&body)
+        .map_err(|_| AccountCreationError::BadTaskRequest)?;
+
+    {
+        let client = client.lock().await;
+
+        // a table can only be opened by one of the players sitting at it, with the consent of the others
+        let opener = match authenticate(&client, &nonces, &req, &body) {
+            Some(account_id) if request.player_ids.contains(&account_id.into()) => account_id,
+            _ => {
+                return Err(AccountCreationError::Unauthorized);
+            }
+        };
+        if !verify_table_consents(&client, &nonces, &request, opener) {
+            return Err(AccountCreationError::Unauthorized);
+        }
     }
 
-    println!("Start sending cards to players");
-    for (i, _) in player_account_ids.iter().enumerate() {
-        let target_account_id = player_account_ids[i];
-        println!("Target account id {:?}", target_account_id);
+    open_game(&client, &keystore, &repository, &snapshots, &request).await.map(Json)
+}
 
-        log_account_status(&client, target_account_id).await;
+/// Opens a table for the players of `request`, seated in the given order, and deals their hole cards.
+///
+/// The table is recorded in the repository once its cards are dealt, along with the deal notes left
+/// in the inbox of the players whose key the server doesn't hold. The deal notes of the others are
+/// consumed right away, and the first snapshot of the table is signed for the seated players. The
+/// client is locked for each transaction rather than for the whole deal.
+///
+/// The key of the game account is kept in `keystore`, the client store only ever sees its public key.
+pub async fn open_game(
+    client: &Mutex<AzeClient>,
+    keystore: &AzeKeyStore,
+    repository: &AzeRepository,
+    snapshots: &SharedAzeSnapshots,
+    request: &GameCreationRequest
+) -> Result<GameCreationResponse, AccountCreationError> {
+    let player_count = request.player_ids.len();
+    if player_count < (MIN_PLAYERS as usize) || player_count > (MAX_PLAYERS as usize) {
+        return Err(AccountCreationError::BadTaskRequest);
+    }
 
-        let input_cards = [cards[i], cards[i + 1]];
-        let sendcard_txn_data = SendCardTransactionData::new(
-            Asset::Fungible(fungible_asset),
-            sender_account_id,
-            target_account_id,
-            &input_cards
-        );
-        let transaction_template = AzeTransactionTemplate::SendCard(sendcard_txn_data);
+    let player_account_ids = request.player_ids
+        .iter()
+        .map(|id| AccountId::try_from(*id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| AccountCreationError::BadTaskRequest)?;
+    // an account takes a single seat
+    let distinct_players: BTreeSet<_> = player_account_ids.iter().collect();
+    if distinct_players.len() != player_account_ids.len() {
+        return Err(AccountCreationError::BadTaskRequest);
+    }
+    let faucet_account_id = AccountId::try_from(request.faucet_id).map_err(
+        |_| AccountCreationError::BadTaskRequest
+    )?;
+
+    let storage_mode = match request.storage_mode {
+        AzeStorageMode::Local => AccountStorageMode::Local,
+        // recording game accounts on chain is not supported by the client yet
+        AzeStorageMode::OnChain => {
+            return Err(AccountCreationError::BadTaskRequest);
+        }
+    };
+
+    let mut locked_client = client.lock().await;
+    // the players are seated and the buy-in is minted from the faucet, so all of them need to be known to the store
+    for account_id in player_account_ids.iter().chain([faucet_account_id].iter()) {
+        locked_client.get_account(*account_id).map_err(|_| AccountCreationError::BadTaskRequest)?;
+    }
+    // looked up before anything is dealt, nothing can fail between dealing and recording the table
+    let custodial_players = player_account_ids
+        .iter()
+        .map(|account_id| is_custodial(&locked_client, *account_id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| AccountCreationError::AccountCreationFailed)?;
+
+    // the deck is shuffled into the game account when it is created, from a seed no player picks
+    let slot_data = GameStorageSlotData::new(
+        request.small_blind,
+        request.buy_in,
+        player_count as u8,
+        FIRST_PLAYER_INDEX,
+        request.small_blind,
+        request.buy_in
+    ).with_shuffle_seed(AzeRng.gen());
+
+    let game_key = SecretKey::with_rng(&mut rand::thread_rng());
+    let (game_account, _) = locked_client
+        .new_aze_game_account_with_key(game_key.public_key(), false, storage_mode, slot_data)
+        .map_err(|_| AccountCreationError::AccountCreationFailed)?;
 
-        let txn_request = client.build_aze_send_card_tx_request(transaction_template).unwrap();
+    let game_account_id = game_account.id();
+    drop(locked_client);
 
-        execute_tx_and_sync(&mut client, txn_request.clone()).await;
+    if let Err(err) = keystore.insert_key(game_account_id, &game_key) {
+        // without its key nobody can deal the table or apply an action to it
+        log::error!("Failed to store the key of game account {game_account_id}: {err}");
+        return Err(AccountCreationError::AccountCreationFailed);
+    }
 
-        let note_id = txn_request.expected_output_notes()[0].id();
-        let note = client.get_input_note(note_id).unwrap();
+    log::info!("Game account {game_account_id} created");
+
+    let deal_notes = match
+        deal_hole_cards_shared(
+            client,
+            game_account_id,
+            &game_key,
+            &player_account_ids,
+            faucet_account_id,
+            request.buy_in as u64
+        ).await
+    {
+        Ok(deal_notes) => deal_notes,
+        Err(err) => {
+            // the account stays in the client store, but no endpoint knows of a table never recorded
+            log::error!("Failed to deal the cards of game account {game_account_id}: {err}");
+            return Err(AccountCreationError::AccountCreationFailed);
+        }
+    };
+
+    let mut custodial_notes = vec![];
+    let mut inbox_notes = vec![];
+    for ((target_account_id, deal_note), is_custodial) in player_account_ids
+        .iter()
+        .zip(deal_notes.iter())
+        .zip(custodial_players) {
+        if is_custodial {
+            custodial_notes.push((*target_account_id, deal_note));
+        } else {
+            // deal notes are private, the player picks theirs up from the inbox
+            inbox_notes.push((*target_account_id, deal_note));
+        }
+    }
 
-        let tx_template = TransactionTemplate::ConsumeNotes(target_account_id, vec![note.id()]);
-        let tx_request = client.build_transaction_request(tx_template).unwrap();
-        execute_tx_and_sync(&mut client, tx_request).await;
+    let table = AzeTable {
+        player_ids: player_account_ids,
+        faucet_id: faucet_account_id,
+    };
+    repository
+        .insert_game(game_account_id, &table, request.small_blind, request.buy_in, &inbox_notes)
+        .map_err(|_| AccountCreationError::AccountCreationFailed)?;
+    // the table is recorded already, players can still follow it from `/v1/game/{game_id}`
+    let snapshot = store_game_snapshot(&*client.lock().await, keystore, snapshots, game_account_id).await;
+    if let Err(err) = snapshot {
+        log::error!("Failed to sign the first snapshot of game account {game_account_id}: {err}");
+    }
 
-        println!("Executed and synced with node");
-        log_account_status(&client, target_account_id).await;
+    for (target_account_id, deal_note) in custodial_notes {
+        // the server holds the player's key, so it consumes the deal note on their behalf
+        let tx_template = TransactionTemplate::ConsumeNotes(target_account_id, vec![deal_note.id()]);
+        let tx_request = client.lock().await.build_transaction_request(tx_template);
+        let consumed = match tx_request {
+            Ok(tx_request) => execute_shared_tx_and_sync(client, tx_request).await.is_ok(),
+            Err(_) => false,
+        };
+        if consumed {
+            log_account_status(&*client.lock().await, target_account_id).await;
+        } else {
+            // the table is dealt already, the player can still pick the note up from the inbox
+            log::error!("Failed to consume the deal note of {target_account_id}, left in its inbox");
+            if let Err(err) = repository.insert_inbox_note(target_account_id, deal_note) {
+                log::error!("Failed to deliver the deal note of {target_account_id}: {err}");
+            }
+        }
     }
 
-    // TODO: define appropriate response types
-    Ok(Json(AccountCreationResponse { is_created: true }))
+    Ok(GameCreationResponse {
+        game_id: game_account_id.into(),
+        deal_note_ids: deal_notes
+            .iter()
+            .map(|deal_note| deal_note.id().to_hex())
+            .collect(),
+    })
 }
 
-#[get("/v1/player/create-account")]
-pub async fn create_aze_player_account() -> Result<
-    Json<PlayerAccountCreationResponse>,
-    AccountCreationError
-> {
-    use miden_objects::accounts::AccountType;
-    // TODO: get some randomness here to pass it in SecretKey::with_rng method
-    let key_pair = SecretKey::new();
-    let pub_key: PublicKey = key_pair.public_key();
-    let auth_scheme: AuthScheme = AuthScheme::RpoFalcon512 { pub_key };
-
-    // we need to use an initial seed to create the wallet account
-    let init_seed: [u8; 32] = [
-        95, 113, 209, 94, 84, 105, 250, 242, 223, 203, 216, 124, 22, 159, 14, 132, 215, 85, 183, 204,
-        149, 90, 166, 68, 100, 73, 106, 168, 125, 237, 138, 16,
-    ];
-
-    let (game_account, _) = create_basic_aze_player_account(
-        init_seed,
-        auth_scheme,
-        AccountType::RegularAccountImmutableCode
-    ).unwrap();
+#[post("/v1/player")]
+pub async fn create_aze_player_account(
+    client: SharedAzeClient,
+    request: Json<PlayerAccountCreationRequest>
+) -> Result<Json<PlayerAccountCreationResponse>, AccountCreationError> {
+    // the player keeps their key, the server only learns the public half to check their signatures
+    let pub_key = RpoDigest::try_from(request.pub_key.as_str())
+        .map(|pub_key| PublicKey::new(pub_key.into()))
+        .map_err(|_| AccountCreationError::BadTaskRequest)?;
+
+    let (player_account, seed) = client
+        .lock().await
+        .new_aze_player_account_with_key(pub_key, AccountStorageMode::Local)
+        .map_err(|_| AccountCreationError::AccountCreationFailed)?;
 
     Ok(
         Json(PlayerAccountCreationResponse {
-            is_created: true,
-            account_id: game_account.id().into(),
+            account_id: player_account.id().into(),
+            account_seed: RpoDigest::from(seed).to_hex(),
         })
     )
 }
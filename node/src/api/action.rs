@@ -1,365 +1,298 @@
-use actix_web::{
-    error::ResponseError,
-    get,
-    http::{ header::ContentType, StatusCode },
-    post,
-    put,
-    web::Data,
-    web::Json,
-    web::Path,
-    HttpResponse,
+use std::time::Instant;
+
+use actix_web::{ post, web::Bytes, web::Json, web::Path, HttpRequest };
+use aze_types::actions::{
+    GameActionError,
+    GameActionRequest,
+    GameState,
+    ProvenTransactionRequest,
+    UnsignedTransactionResponse,
 };
-use derive_more::Display;
-use aze_types::actions::{ GameActionError, GameActionResponse };
-use aze_lib::utils::{ log_account_status, log_slots };
-use aze_lib::storage::GameStorageSlotData;
-use aze_lib::executor::execute_tx_and_sync;
-use aze_lib::constants::BUY_IN_AMOUNT;
-use aze_lib::client::{
-    AzeClient,
-    AzeAccountTemplate,
-    create_aze_client,
-    PlayRaiseTransactionData,
-    PlayCallTransactionData,
-    PlayFoldTransactionData,
-    PlayCheckTransactionData,
-    AzeTransactionTemplate,
-    AzeGameMethods,
+use aze_lib::auth::NonceRegistry;
+use aze_lib::contracts::is_compatible_game_code;
+use aze_lib::keystore::AzeKeyStore;
+use aze_lib::storage::read_game_state;
+use aze_lib::table::action_transaction_template;
+use aze_lib::transport::import_private_note;
+use aze_lib::executor::{
+    execute_shared_tx_and_sync,
+    serialize_unsigned_tx_request,
+    submit_proven_tx_and_sync,
 };
-use miden_client::client::{
-    accounts::{ AccountStorageMode, AccountTemplate },
-    transactions::transaction_request::TransactionTemplate,
+use aze_lib::client::{ AzeClient, AzeTransactionTemplate, AzeGameMethods };
+use miden_objects::{
+    accounts::AccountId,
+    notes::NoteId,
+    transaction::ProvenTransaction,
+    utils::Deserializable,
+};
+use tokio::sync::Mutex;
+use crate::auth::authenticate;
+use crate::api::snapshots::store_game_snapshot;
+use crate::repository::TableAction;
+use crate::state::{
+    SharedAzeClient,
+    SharedAzeKeyStore,
+    SharedAzeNonces,
+    SharedAzePendingActions,
+    SharedAzeRepository,
+    SharedAzeSnapshots,
 };
-use miden_objects::{ assets::{ TokenSymbol, Asset, FungibleAsset }, notes::NoteType };
-use aze_lib::notes::{ consume_notes, mint_note };
-
-#[post("/v1/game/action")]
-pub async fn aze_poker_game_action() -> Result<Json<GameActionResponse>, GameActionError> {
-    let mut client: AzeClient = create_aze_client();
-
-    let small_blind_amt = 5u8;
-    let buy_in_amt = 100u8;
-    let no_of_players = 4u8;
-    let current_turn_index = 64u8;
-    let player_balance = 10u8;
-
-    let slot_data = GameStorageSlotData::new(
-        small_blind_amt,
-        buy_in_amt,
-        no_of_players,
-        current_turn_index,
-        small_blind_amt,
-        player_balance
-    );
-
-    let (game_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::GameAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            Some(slot_data)
-        )
-        .unwrap();
-    let game_account_id = game_account.id();
-    log_slots(&client, game_account_id).await;
-
-    let (player_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::PlayerAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            None
-        )
-        .unwrap();
-    let player_account_id = player_account.id();
-
-    let (faucet_account, _) = client
-        .new_account(AccountTemplate::FungibleFaucet {
-            token_symbol: TokenSymbol::new("MATIC").unwrap(),
-            decimals: 8,
-            max_supply: 1_000_000_000,
-            storage_mode: AccountStorageMode::Local,
-        })
-        .unwrap();
-    let faucet_account_id = faucet_account.id();
-
-    let note = mint_note(&mut client, player_account_id, faucet_account_id, NoteType::Public).await;
-    println!("Minted note");
-    consume_notes(&mut client, player_account_id, &[note]).await;
-
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-    let sender_account_id = player_account_id;
-    let target_account_id = game_account_id;
-
-    let player_bet = small_blind_amt;
 
-    let playraise_txn_data = PlayRaiseTransactionData::new(
-        Asset::Fungible(fungible_asset),
-        sender_account_id,
-        game_account_id,
-        player_bet
-    );
-    let transaction_template = AzeTransactionTemplate::PlayRaise(playraise_txn_data);
-    let txn_request = client.build_aze_play_raise_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(&mut client, txn_request.clone()).await;
+#[post("/v1/game/{game_id}/actions")]
+pub async fn aze_poker_game_action(
+    client: SharedAzeClient,
+    keystore: SharedAzeKeyStore,
+    nonces: SharedAzeNonces,
+    repository: SharedAzeRepository,
+    snapshots: SharedAzeSnapshots,
+    game_id: Path<u64>,
+    req: HttpRequest,
+    body: Bytes
+) -> Result<Json<GameState>, GameActionError> {
+    let (game_account_id, txn_request, action) = {
+        let mut client = client.lock().await;
+        let (game_account_id, transaction_template, action) = validate_action(
+            &client,
+            &nonces,
+            &repository,
+            game_id.into_inner(),
+            &req,
+            &body
+        )?;
+
+        let txn_request = client
+            .build_aze_tx_request(transaction_template)
+            .map_err(|_| GameActionError::GameActionFailed)?;
+        (game_account_id, txn_request, action)
+    };
+
+    // the client is only locked for each sync while waiting, other tables keep being served meanwhile
+    execute_shared_tx_and_sync(&client, txn_request.clone()).await.map_err(
+        |_| GameActionError::GameActionFailed
+    )?;
 
     let note_id = txn_request.expected_output_notes()[0].id();
-    let note = client.get_input_note(note_id).unwrap();
-
-    let tx_template = TransactionTemplate::ConsumeNotes(target_account_id, vec![note.id()]);
-    let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(&mut client, tx_request).await;
-
-    println!("Executed and synced with node");
-    log_slots(&client, target_account_id).await;
-
-    Ok(Json(GameActionResponse { is_taken: true }))
+    consume_action_note(
+        &client,
+        &keystore,
+        &repository,
+        &snapshots,
+        game_account_id,
+        note_id,
+        &action
+    ).await.map(Json)
 }
 
-#[post("/v1/game/call")]
-pub async fn aze_poker_game_call() -> Result<Json<GameActionResponse>, GameActionError> {
-    let mut client: AzeClient = create_aze_client();
-
-    let small_blind_amt = 5u8;
-    let buy_in_amt = 100u8;
-    let no_of_players = 4u8;
-    let current_turn_index = 64u8;
-    let player_balance = 10u8;
-
-    let slot_data = GameStorageSlotData::new(
-        small_blind_amt,
-        buy_in_amt,
-        no_of_players,
-        current_turn_index,
-        small_blind_amt,
-        player_balance
-    );
-
-    let (game_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::GameAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            Some(slot_data)
-        )
-        .unwrap();
-    let game_account_id = game_account.id();
-    log_slots(&client, game_account_id).await;
-
-    let (player_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::PlayerAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            None
-        )
-        .unwrap();
-    let player_account_id = player_account.id();
-
-    let (faucet_account, _) = client
-        .new_account(AccountTemplate::FungibleFaucet {
-            token_symbol: TokenSymbol::new("MATIC").unwrap(),
-            decimals: 8,
-            max_supply: 1_000_000_000,
-            storage_mode: AccountStorageMode::Local,
+/// Same checks as `aze_poker_game_action`, but the request is handed back unsigned so the player
+/// can sign and prove it on their own machine before sending it to `/transactions`.
+#[post("/v1/game/{game_id}/actions/request")]
+pub async fn aze_poker_game_action_request(
+    client: SharedAzeClient,
+    nonces: SharedAzeNonces,
+    repository: SharedAzeRepository,
+    pending_actions: SharedAzePendingActions,
+    game_id: Path<u64>,
+    req: HttpRequest,
+    body: Bytes
+) -> Result<Json<UnsignedTransactionResponse>, GameActionError> {
+    let mut client = client.lock().await;
+    let (game_account_id, transaction_template, action) = validate_action(
+        &client,
+        &nonces,
+        &repository,
+        game_id.into_inner(),
+        &req,
+        &body
+    )?;
+    let txn_request = client
+        .build_aze_unsigned_tx_request(transaction_template)
+        .map_err(|_| GameActionError::GameActionFailed)?;
+
+    // a newer request replaces the one the player did not prove
+    pending_actions.insert(
+        game_account_id,
+        txn_request.account_id(),
+        txn_request.expected_output_notes()[0].clone(),
+        action,
+        Instant::now()
+    ).await;
+
+    Ok(
+        Json(UnsignedTransactionResponse {
+            transaction_request: hex::encode(serialize_unsigned_tx_request(&txn_request)),
         })
-        .unwrap();
-    let faucet_account_id = faucet_account.id();
-
-    let note = mint_note(&mut client, player_account_id, faucet_account_id, NoteType::Public).await;
-    println!("Minted note");
-    consume_notes(&mut client, player_account_id, &[note]).await;
-
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-    let sender_account_id = player_account_id;
-    let target_account_id = game_account_id;
-
-    let playcall_txn_data = PlayCallTransactionData::new(
-        Asset::Fungible(fungible_asset),
-        sender_account_id,
-        game_account_id
-    );
-    let transaction_template = AzeTransactionTemplate::PlayCall(playcall_txn_data);
-    let txn_request = client.build_aze_play_call_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(&mut client, txn_request.clone()).await;
-
-    let note_id = txn_request.expected_output_notes()[0].id();
-    let note = client.get_input_note(note_id).unwrap();
-
-    let tx_template = TransactionTemplate::ConsumeNotes(target_account_id, vec![note.id()]);
-    let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(&mut client, tx_request).await;
-
-    println!("Executed and synced with node");
-    log_slots(&client, target_account_id).await;
-
-    Ok(Json(GameActionResponse { is_taken: true }))
+    )
 }
 
-#[post("/v1/game/fold")]
-pub async fn aze_poker_game_fold() -> Result<Json<GameActionResponse>, GameActionError> {
-    let mut client: AzeClient = create_aze_client();
-
-    let small_blind_amt = 5u8;
-    let buy_in_amt = 100u8;
-    let no_of_players = 4u8;
-    let current_turn_index = 64u8;
-    let player_balance = 10u8;
-
-    let slot_data = GameStorageSlotData::new(
-        small_blind_amt,
-        buy_in_amt,
-        no_of_players,
-        current_turn_index,
-        small_blind_amt,
-        player_balance
-    );
-
-    let (game_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::GameAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            Some(slot_data)
-        )
-        .unwrap();
-    let game_account_id = game_account.id();
-    log_slots(&client, game_account_id).await;
-
-    let (player_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::PlayerAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            None
-        )
-        .unwrap();
-    let player_account_id = player_account.id();
-
-    let (faucet_account, _) = client
-        .new_account(AccountTemplate::FungibleFaucet {
-            token_symbol: TokenSymbol::new("MATIC").unwrap(),
-            decimals: 8,
-            max_supply: 1_000_000_000,
-            storage_mode: AccountStorageMode::Local,
-        })
-        .unwrap();
-    let faucet_account_id = faucet_account.id();
-
-    let note = mint_note(&mut client, player_account_id, faucet_account_id, NoteType::Public).await;
-    println!("Minted note");
-    consume_notes(&mut client, player_account_id, &[note]).await;
-
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-    let sender_account_id = player_account_id;
-    let target_account_id = game_account_id;
-    
-    let playcall_txn_data = PlayFoldTransactionData::new(
-        Asset::Fungible(fungible_asset),
-        sender_account_id,
-        game_account_id
-    );
-    let transaction_template = AzeTransactionTemplate::PlayFold(playcall_txn_data);
-    let txn_request = client.build_aze_play_fold_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(&mut client, txn_request.clone()).await;
-
-    let note_id = txn_request.expected_output_notes()[0].id();
-    let note = client.get_input_note(note_id).unwrap();
-
-    let tx_template = TransactionTemplate::ConsumeNotes(target_account_id, vec![note.id()]);
-    let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(&mut client, tx_request).await;
-
-    println!("Executed and synced with node");
-    log_slots(&client, target_account_id).await;
-
-    Ok(Json(GameActionResponse { is_taken: true }))
+#[post("/v1/game/{game_id}/transactions")]
+pub async fn aze_submit_proven_transaction(
+    client: SharedAzeClient,
+    keystore: SharedAzeKeyStore,
+    nonces: SharedAzeNonces,
+    repository: SharedAzeRepository,
+    pending_actions: SharedAzePendingActions,
+    snapshots: SharedAzeSnapshots,
+    game_id: Path<u64>,
+    req: HttpRequest,
+    body: Bytes
+) -> Result<Json<GameState>, GameActionError> {
+    let request: ProvenTransactionRequest = serde_json
+        ::from_slice(&body)
+        .map_err(|_| GameActionError::BadActionRequest)?;
+    let game_account_id = AccountId::try_from(game_id.into_inner()).map_err(
+        |_| GameActionError::BadActionRequest
+    )?;
+    let player_account_id = AccountId::try_from(request.player_id).map_err(
+        |_| GameActionError::BadActionRequest
+    )?;
+
+    let proven_transaction = hex
+        ::decode(&request.proven_transaction)
+        .ok()
+        .and_then(|bytes| ProvenTransaction::read_from_bytes(&bytes).ok())
+        .ok_or(GameActionError::BadActionRequest)?;
+    if proven_transaction.account_id() != player_account_id {
+        return Err(GameActionError::BadActionRequest);
+    }
+
+    let (note_id, action) = {
+        let mut client = client.lock().await;
+
+        if authenticate(&client, &nonces, &req, &body) != Some(player_account_id) {
+            return Err(GameActionError::Unauthorized);
+        }
+
+        let (note, action) = pending_actions
+            .take(game_account_id, player_account_id, Instant::now()).await
+            .ok_or(GameActionError::BadActionRequest)?;
+        let note_id = note.id();
+
+        // the note was created on the player's machine, so it is only known to this store once imported
+        import_private_note(&mut client, note).map_err(|_| GameActionError::GameActionFailed)?;
+        (note_id, action)
+    };
+
+    // the client is only locked for each sync while waiting, other tables keep being served meanwhile
+    let is_committed = submit_proven_tx_and_sync(&client, proven_transaction, note_id).await.map_err(
+        |_| GameActionError::GameActionFailed
+    )?;
+    // a transaction that does not create the note it was handed out for never shows up here
+    if !is_committed {
+        return Err(GameActionError::GameActionFailed);
+    }
+
+    consume_action_note(
+        &client,
+        &keystore,
+        &repository,
+        &snapshots,
+        game_account_id,
+        note_id,
+        &action
+    ).await.map(Json)
 }
 
-#[post("/v1/game/check")]
-pub async fn aze_poker_game_check() -> Result<Json<GameActionResponse>, GameActionError> {
-    let mut client: AzeClient = create_aze_client();
-
-    let small_blind_amt = 5u8;
-    let buy_in_amt = 100u8;
-    let no_of_players = 4u8;
-    let current_turn_index = 64u8;
-    let player_balance = 10u8;
-
-    let slot_data = GameStorageSlotData::new(
-        small_blind_amt,
-        buy_in_amt,
-        no_of_players,
-        current_turn_index,
-        small_blind_amt,
-        player_balance
-    );
+// checks a signed action against the table and returns the transaction that plays it
+fn validate_action(
+    client: &AzeClient,
+    nonces: &NonceRegistry,
+    repository: &SharedAzeRepository,
+    game_id: u64,
+    req: &HttpRequest,
+    body: &[u8]
+) -> Result<(AccountId, AzeTransactionTemplate, TableAction), GameActionError> {
+    // the body is parsed by hand since the signature covers its raw bytes
+    let request: GameActionRequest = serde_json
+        ::from_slice(body)
+        .map_err(|_| GameActionError::BadActionRequest)?;
+    let game_account_id = AccountId::try_from(game_id).map_err(
+        |_| GameActionError::BadActionRequest
+    )?;
+    let player_account_id = AccountId::try_from(request.player_id).map_err(
+        |_| GameActionError::BadActionRequest
+    )?;
+
+    // checked before the table is looked up, so nobody learns who sits where without signing as them
+    if authenticate(client, nonces, req, body) != Some(player_account_id) {
+        return Err(GameActionError::Unauthorized);
+    }
+
+    let table = repository
+        .table(game_account_id)
+        .map_err(|_| GameActionError::GameActionFailed)?
+        .ok_or(GameActionError::BadActionRequest)?;
+    let seat = table.seat_of(player_account_id).ok_or(GameActionError::BadActionRequest)?;
 
     let (game_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::GameAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            Some(slot_data)
-        )
-        .unwrap();
-    let game_account_id = game_account.id();
-    log_slots(&client, game_account_id).await;
-
-    let (player_account, _) = client
-        .new_game_account(
-            AzeAccountTemplate::PlayerAccount {
-                mutable_code: false,
-                storage_mode: AccountStorageMode::Local, // for now
-            },
-            None
-        )
-        .unwrap();
-    let player_account_id = player_account.id();
-
-    let (faucet_account, _) = client
-        .new_account(AccountTemplate::FungibleFaucet {
-            token_symbol: TokenSymbol::new("MATIC").unwrap(),
-            decimals: 8,
-            max_supply: 1_000_000_000,
-            storage_mode: AccountStorageMode::Local,
-        })
-        .unwrap();
-    let faucet_account_id = faucet_account.id();
-
-    let note = mint_note(&mut client, player_account_id, faucet_account_id, NoteType::Public).await;
-    println!("Minted note");
-    consume_notes(&mut client, player_account_id, &[note]).await;
-
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-    let sender_account_id = player_account_id;
-    let target_account_id = game_account_id;
-    
-    let playcheck_txn_data = PlayCheckTransactionData::new(
-        Asset::Fungible(fungible_asset),
-        sender_account_id,
-        game_account_id
-    );
-    let transaction_template = AzeTransactionTemplate::PlayCheck(playcheck_txn_data);
-    let txn_request = client.build_aze_play_check_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(&mut client, txn_request.clone()).await;
-
-    let note_id = txn_request.expected_output_notes()[0].id();
-    let note = client.get_input_note(note_id).unwrap();
-
-    let tx_template = TransactionTemplate::ConsumeNotes(target_account_id, vec![note.id()]);
-    let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(&mut client, tx_request).await;
-
-    println!("Executed and synced with node");
-    log_slots(&client, target_account_id).await;
+        .get_account(game_account_id)
+        .map_err(|_| GameActionError::BadActionRequest)?;
+    // tables created by a build with different game code can't take notes built by this one
+    if !is_compatible_game_code(game_account.code()) {
+        return Err(GameActionError::GameActionFailed);
+    }
+    let transaction_template = action_transaction_template(
+        &read_game_state(game_account.storage()),
+        seat,
+        player_account_id,
+        game_account_id,
+        table.faucet_id,
+        request.action,
+        request.amount
+    ).map_err(|_| GameActionError::BadActionRequest)?;
+
+    let action = TableAction {
+        seat,
+        player_id: player_account_id,
+        action: request.action,
+        amount: request.amount,
+    };
+    Ok((game_account_id, transaction_template, action))
+}
 
-    Ok(Json(GameActionResponse { is_taken: true }))
-}
\ No newline at end of file
+// the game account consumes the action note, which is what applies the action to the table
+async fn consume_action_note(
+    client: &Mutex<AzeClient>,
+    keystore: &AzeKeyStore,
+    repository: &SharedAzeRepository,
+    snapshots: &SharedAzeSnapshots,
+    game_account_id: AccountId,
+    note_id: NoteId,
+    action: &TableAction
+) -> Result<GameState, GameActionError> {
+    let tx_request = {
+        let mut client = client.lock().await;
+        let note = client.get_input_note(note_id).map_err(|_| GameActionError::GameActionFailed)?;
+
+        // signed with the game key from the keystore, the one in the client store is a placeholder
+        let game_auth = keystore
+            .account_auth(&client, game_account_id)
+            .map_err(|_| GameActionError::GameActionFailed)?;
+        client
+            .build_consume_notes_tx_request(game_account_id, vec![note.id()], game_auth)
+            .map_err(|_| GameActionError::GameActionFailed)?
+    };
+    execute_shared_tx_and_sync(client, tx_request).await.map_err(
+        |_| GameActionError::GameActionFailed
+    )?;
+
+    let game_state = {
+        let client = client.lock().await;
+        let (game_account, _) = client
+            .get_account(game_account_id)
+            .map_err(|_| GameActionError::GameActionFailed)?;
+
+        // the action is on chain already, the seated players fall back to the decoded table meanwhile
+        if let Err(err) = store_game_snapshot(&client, keystore, snapshots, game_account_id).await {
+            log::error!("Failed to sign the snapshot of game account {game_account_id}: {err}");
+        }
+        read_game_state(game_account.storage())
+    };
+
+    // the action is on chain by now, a failure to record it is not the player's to deal with
+    if let Err(err) = repository.record_action(game_account_id, action, note_id, &game_state) {
+        log::error!("Failed to record action: {err}");
+    }
+
+    Ok(game_state)
+}
@@ -0,0 +1,45 @@
+use actix_web::{ get, web::{ Bytes, Path }, HttpResponse };
+use aze_lib::storage::read_game_state;
+use aze_types::actions::GameActionError;
+use aze_types::events::GameEvent;
+use miden_objects::accounts::AccountId;
+use tokio_stream::{ wrappers::BroadcastStream, StreamExt };
+use crate::state::{ SharedAzeClient, SharedAzeSubscriptions };
+
+fn sse_frame(event: &GameEvent) -> Bytes {
+    Bytes::from(format!("data: {}\n\n", serde_json::to_string(event).unwrap()))
+}
+
+// server-sent events stream of a table, starting with a snapshot followed by the diffs pushed by the sync task
+#[get("/v1/game/{game_id}/events")]
+pub async fn aze_game_events(
+    client: SharedAzeClient,
+    subscriptions: SharedAzeSubscriptions,
+    game_id: Path<u64>
+) -> Result<HttpResponse, GameActionError> {
+    let game_account_id = AccountId::try_from(game_id.into_inner()).map_err(
+        |_| GameActionError::BadActionRequest
+    )?;
+
+    let (game_account, _) = client
+        .lock().await
+        .get_account(game_account_id)
+        .map_err(|_| GameActionError::BadActionRequest)?;
+
+    let state = read_game_state(game_account.storage());
+    let receiver = subscriptions.subscribe(game_account_id, game_account.hash(), state.clone());
+    let snapshot = GameEvent::Snapshot { state };
+
+    // a lagging subscriber skips the events it missed instead of dropping the connection
+    let updates = BroadcastStream::new(receiver).filter_map(|event| event.ok());
+    let stream = tokio_stream::once(snapshot)
+        .chain(updates)
+        .map(|event| Ok::<_, actix_web::Error>(sse_frame(&event)));
+
+    Ok(
+        HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header(("Cache-Control", "no-cache"))
+            .streaming(stream)
+    )
+}
@@ -0,0 +1,68 @@
+use actix_web::{ get, post, web::Bytes, web::Json, web::Path, HttpRequest, HttpResponse };
+use aze_lib::notes::game_note_tag;
+use aze_lib::transport::{ decode_note, encode_note };
+use aze_types::notes::{ NoteInboxError, PrivateNoteDelivery, PrivateNotesResponse };
+use miden_objects::accounts::AccountId;
+use crate::auth::authenticate;
+use crate::state::{ SharedAzeClient, SharedAzeNonces, SharedAzeRepository };
+
+#[post("/v1/notes")]
+pub async fn deliver_private_note(
+    client: SharedAzeClient,
+    nonces: SharedAzeNonces,
+    repository: SharedAzeRepository,
+    req: HttpRequest,
+    body: Bytes
+) -> Result<HttpResponse, NoteInboxError> {
+    let delivery: PrivateNoteDelivery = serde_json
+        ::from_slice(&body)
+        .map_err(|_| NoteInboxError::BadNoteRequest)?;
+    let recipient_id = AccountId::try_from(delivery.recipient_id).map_err(
+        |_| NoteInboxError::BadNoteRequest
+    )?;
+    let note = decode_note(&delivery.note).ok_or(NoteInboxError::BadNoteRequest)?;
+
+    // only the sender of a note can drop it in an inbox
+    if authenticate(&client.lock().await, &nonces, &req, &body) != Some(note.metadata().sender()) {
+        return Err(NoteInboxError::Unauthorized);
+    }
+
+    // game notes carry the tag of their table, which is either the sender or the recipient
+    let is_game_note = [note.metadata().sender(), recipient_id]
+        .into_iter()
+        .filter_map(|account_id| game_note_tag(account_id).ok())
+        .any(|tag| tag == note.metadata().tag());
+    if !is_game_note {
+        return Err(NoteInboxError::BadNoteRequest);
+    }
+
+    repository.insert_inbox_note(recipient_id, &note).map_err(|_| NoteInboxError::InboxFailed)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/v1/player/{player_id}/inbox")]
+pub async fn get_private_notes(
+    client: SharedAzeClient,
+    nonces: SharedAzeNonces,
+    repository: SharedAzeRepository,
+    player_id: Path<u64>,
+    req: HttpRequest
+) -> Result<Json<PrivateNotesResponse>, NoteInboxError> {
+    let player_account_id = AccountId::try_from(player_id.into_inner()).map_err(
+        |_| NoteInboxError::BadNoteRequest
+    )?;
+
+    if authenticate(&client.lock().await, &nonces, &req, &[]) != Some(player_account_id) {
+        return Err(NoteInboxError::Unauthorized);
+    }
+
+    // notes stay in the inbox until the sync task sees them consumed, so a client that lost them can fetch them again
+    let notes = repository.inbox(player_account_id).map_err(|_| NoteInboxError::InboxFailed)?;
+
+    Ok(
+        Json(PrivateNotesResponse {
+            notes: notes.iter().map(encode_note).collect(),
+        })
+    )
+}
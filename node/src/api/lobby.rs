@@ -0,0 +1,154 @@
+use actix_web::{ delete, get, post, web::Bytes, web::Json, web::Path, HttpRequest, HttpResponse };
+use aze_types::accounts::{
+    AccountCreationError,
+    AccountQueryError,
+    AzeStorageMode,
+    GameCreationRequest,
+};
+use aze_types::lobby::{ LobbyResponse, QueueRequest, QueueStatus, QueueSummary };
+use miden_objects::accounts::AccountId;
+use crate::api::accounts::open_game;
+use crate::auth::authenticate;
+use crate::state::{
+    SharedAzeClient,
+    SharedAzeKeyStore,
+    SharedAzeLobby,
+    SharedAzeNonces,
+    SharedAzeRepository,
+    SharedAzeSnapshots,
+};
+
+#[get("/v1/lobby")]
+pub async fn get_lobby(
+    repository: SharedAzeRepository,
+    lobby: SharedAzeLobby
+) -> Result<Json<LobbyResponse>, AccountQueryError> {
+    let tables = repository.open_tables().map_err(|_| AccountQueryError::QueryFailed)?;
+    let queues = lobby
+        .queues()
+        .into_iter()
+        .map(|(stakes, waiting)| QueueSummary { stakes, waiting })
+        .collect();
+
+    Ok(
+        Json(LobbyResponse {
+            tables,
+            table_seats: lobby.table_seats(),
+            queues,
+        })
+    )
+}
+
+#[post("/v1/lobby/queue")]
+pub async fn join_queue(
+    client: SharedAzeClient,
+    keystore: SharedAzeKeyStore,
+    nonces: SharedAzeNonces,
+    repository: SharedAzeRepository,
+    snapshots: SharedAzeSnapshots,
+    lobby: SharedAzeLobby,
+    req: HttpRequest,
+    body: Bytes
+) -> Result<Json<QueueStatus>, AccountCreationError> {
+    // the body is parsed by hand since the signature covers its raw bytes
+    let request: QueueRequest = serde_json
+        ::from_slice(&body)
+        .map_err(|_| AccountCreationError::BadTaskRequest)?;
+    let player_account_id = AccountId::try_from(request.player_id).map_err(
+        |_| AccountCreationError::BadTaskRequest
+    )?;
+    let faucet_account_id = AccountId::try_from(request.stakes.faucet_id).map_err(
+        |_| AccountCreationError::BadTaskRequest
+    )?;
+
+    {
+        let client = client.lock().await;
+
+        if authenticate(&client, &nonces, &req, &body) != Some(player_account_id) {
+            return Err(AccountCreationError::Unauthorized);
+        }
+
+        // checked up front, a player unknown to the store would keep everybody behind from being seated
+        for account_id in [player_account_id, faucet_account_id] {
+            client.get_account(account_id).map_err(|_| AccountCreationError::BadTaskRequest)?;
+        }
+    }
+
+    // a full queue is drained at once, so only this request opens a table for the players taken from it.
+    // The lobby is not kept locked while the table is opened, other players can queue in the meantime
+    let player_account_ids = lobby
+        .join(request.stakes, player_account_id)
+        .map_err(|_| AccountCreationError::AccountCreationFailed)?
+        .ok_or(AccountCreationError::BadTaskRequest)?;
+    if player_account_ids.is_empty() {
+        return lobby.status(player_account_id).map(Json).ok_or(AccountCreationError::BadTaskRequest);
+    }
+
+    // players are seated in the order they joined the queue, each of them agreed to the stakes by signing
+    // their queue request
+    let game_request = GameCreationRequest {
+        player_ids: player_account_ids
+            .iter()
+            .map(|player_id| (*player_id).into())
+            .collect(),
+        small_blind: request.stakes.small_blind,
+        buy_in: request.stakes.buy_in,
+        faucet_id: request.stakes.faucet_id,
+        storage_mode: AzeStorageMode::Local,
+        consents: vec![],
+    };
+    match open_game(&client, &keystore, &repository, &snapshots, &game_request).await {
+        Ok(game) => {
+            let game_account_id = AccountId::try_from(game.game_id).map_err(
+                |_| AccountCreationError::AccountCreationFailed
+            )?;
+            // the table is open either way, the players only lose track of it in the lobby
+            if let Err(err) = lobby.seat(&player_account_ids, game_account_id) {
+                log::error!("Failed to record the players seated at {game_account_id}: {err}");
+            }
+
+            Ok(Json(QueueStatus::Seated { game_id: game.game_id }))
+        }
+        Err(err) => {
+            if let Err(requeue_err) = lobby.requeue(request.stakes, player_account_ids) {
+                log::error!("Failed to put the players of an unopened table back in the queue: {requeue_err}");
+            }
+            Err(err)
+        }
+    }
+}
+
+#[get("/v1/lobby/queue/{player_id}")]
+pub async fn get_queue_status(
+    lobby: SharedAzeLobby,
+    player_id: Path<u64>
+) -> Result<Json<QueueStatus>, AccountQueryError> {
+    let player_account_id = AccountId::try_from(player_id.into_inner()).map_err(
+        |_| AccountQueryError::BadQueryRequest
+    )?;
+
+    lobby.status(player_account_id).map(Json).ok_or(AccountQueryError::AccountNotFound)
+}
+
+#[delete("/v1/lobby/queue/{player_id}")]
+pub async fn leave_queue(
+    client: SharedAzeClient,
+    nonces: SharedAzeNonces,
+    lobby: SharedAzeLobby,
+    player_id: Path<u64>,
+    req: HttpRequest
+) -> Result<HttpResponse, AccountQueryError> {
+    let player_account_id = AccountId::try_from(player_id.into_inner()).map_err(
+        |_| AccountQueryError::BadQueryRequest
+    )?;
+
+    if authenticate(&client.lock().await, &nonces, &req, &[]) != Some(player_account_id) {
+        return Err(AccountQueryError::Unauthorized);
+    }
+
+    if !lobby.leave(player_account_id).map_err(|_| AccountQueryError::QueryFailed)? {
+        return Err(AccountQueryError::AccountNotFound);
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -1,3 +1,8 @@
 pub mod accounts;
 pub mod action;
-pub mod notes;
\ No newline at end of file
+pub mod events;
+pub mod inbox;
+pub mod lobby;
+pub mod notes;
+pub mod query;
+pub mod snapshots;
\ No newline at end of file
@@ -1,7 +1,5 @@
 use aze_lib::accounts::{create_basic_aze_game_account, create_basic_aze_player_account};
 use aze_lib::client::create_aze_client;
-use aze_lib::notes::create_send_card_note;
-use aze_lib::utils::{get_new_key_pair_with_advice_map};
 use aze_lib::constants::DEFAULT_AUTH_SCRIPT;
 use miden_lib::AuthScheme;
 use miden_objects::{
@@ -0,0 +1,152 @@
+use actix_web::{ get, web::Json, web::Path, HttpRequest };
+use aze_lib::notes::game_note_tag;
+use aze_lib::storage::{ read_game_state, read_hole_cards };
+use aze_types::accounts::{
+    AccountQueryError,
+    AssetBalance,
+    PlayerStateResponse,
+    TableSummary,
+    TablesResponse,
+};
+use aze_types::actions::{ ActionsResponse, GameState };
+use aze_types::notes::{ GameNotesResponse, NoteSummary };
+use miden_client::store::NoteFilter;
+use miden_objects::{ accounts::AccountId, assets::Asset };
+use crate::auth::authenticate;
+use crate::state::{ SharedAzeClient, SharedAzeNonces, SharedAzeRepository };
+
+fn parse_account_id(account_id: u64) -> Result<AccountId, AccountQueryError> {
+    AccountId::try_from(account_id).map_err(|_| AccountQueryError::BadQueryRequest)
+}
+
+#[get("/v1/games")]
+pub async fn get_aze_games(
+    repository: SharedAzeRepository
+) -> Result<Json<TablesResponse>, AccountQueryError> {
+    let tables = repository
+        .tables()
+        .map_err(|_| AccountQueryError::QueryFailed)?
+        .into_iter()
+        .map(|(game_account_id, table)| TableSummary {
+            game_id: game_account_id.into(),
+            player_ids: table.player_ids
+                .iter()
+                .map(|player_id| (*player_id).into())
+                .collect(),
+            faucet_id: table.faucet_id.into(),
+        })
+        .collect();
+
+    Ok(Json(TablesResponse { tables }))
+}
+
+#[get("/v1/game/{game_id}/history")]
+pub async fn get_aze_game_history(
+    repository: SharedAzeRepository,
+    game_id: Path<u64>
+) -> Result<Json<ActionsResponse>, AccountQueryError> {
+    let game_account_id = parse_account_id(game_id.into_inner())?;
+
+    repository
+        .table(game_account_id)
+        .map_err(|_| AccountQueryError::QueryFailed)?
+        .ok_or(AccountQueryError::AccountNotFound)?;
+    let actions = repository.actions(game_account_id).map_err(|_| AccountQueryError::QueryFailed)?;
+
+    Ok(Json(ActionsResponse { actions }))
+}
+
+#[get("/v1/game/{game_id}")]
+pub async fn get_aze_game(
+    client: SharedAzeClient,
+    game_id: Path<u64>
+) -> Result<Json<GameState>, AccountQueryError> {
+    let game_account_id = parse_account_id(game_id.into_inner())?;
+
+    let (game_account, _) = client
+        .lock().await
+        .get_account(game_account_id)
+        .map_err(|_| AccountQueryError::AccountNotFound)?;
+
+    // hole cards live in the player accounts, the game state only carries revealed community cards
+    Ok(Json(read_game_state(game_account.storage())))
+}
+
+#[get("/v1/player/{player_id}")]
+pub async fn get_aze_player(
+    client: SharedAzeClient,
+    nonces: SharedAzeNonces,
+    player_id: Path<u64>,
+    req: HttpRequest
+) -> Result<Json<PlayerStateResponse>, AccountQueryError> {
+    let player_account_id = parse_account_id(player_id.into_inner())?;
+
+    let client = client.lock().await;
+    let (player_account, _) = client
+        .get_account(player_account_id)
+        .map_err(|_| AccountQueryError::AccountNotFound)?;
+
+    // hole cards are only shown to the player, who proves it by signing the request
+    let is_owner = authenticate(&client, &nonces, &req, &[]) == Some(player_account_id);
+
+    let balances = player_account
+        .vault()
+        .assets()
+        .filter_map(|asset| match asset {
+            Asset::Fungible(asset) =>
+                Some(AssetBalance {
+                    faucet_id: asset.faucet_id().into(),
+                    amount: asset.amount(),
+                }),
+            Asset::NonFungible(_) => None,
+        })
+        .collect();
+
+    let hole_cards = if is_owner { read_hole_cards(player_account.storage()) } else { vec![] };
+
+    Ok(
+        Json(PlayerStateResponse {
+            account_id: player_account_id.into(),
+            balances,
+            hole_cards,
+        })
+    )
+}
+
+#[get("/v1/game/{game_id}/notes")]
+pub async fn get_aze_game_notes(
+    client: SharedAzeClient,
+    game_id: Path<u64>
+) -> Result<Json<GameNotesResponse>, AccountQueryError> {
+    let game_account_id = parse_account_id(game_id.into_inner())?;
+    let game_tag = game_note_tag(game_account_id).map_err(|_| AccountQueryError::BadQueryRequest)?;
+
+    let client = client.lock().await;
+    client.get_account_stub_by_id(game_account_id).map_err(|_| AccountQueryError::AccountNotFound)?;
+
+    let mut notes = vec![];
+    for (filter, status) in [
+        (NoteFilter::Pending, "pending"),
+        (NoteFilter::Committed, "committed"),
+    ] {
+        let input_notes = client
+            .get_input_notes(filter)
+            .map_err(|_| AccountQueryError::BadQueryRequest)?;
+
+        for note in input_notes {
+            let Some(metadata) = note.metadata() else {
+                continue;
+            };
+            if metadata.tag() != game_tag {
+                continue;
+            }
+            notes.push(NoteSummary {
+                note_id: note.id().to_hex(),
+                sender_id: metadata.sender().into(),
+                status: status.to_string(),
+            });
+        }
+    }
+
+    Ok(Json(GameNotesResponse { notes }))
+}
@@ -0,0 +1,58 @@
+use actix_web::{ get, web::Json, web::Path, HttpRequest };
+use aze_lib::client::AzeClient;
+use aze_lib::keystore::{ AzeKeyStore, KeyStoreError };
+use aze_lib::transport::sign_game_snapshot;
+use aze_types::accounts::{ AccountQueryError, GameSnapshot };
+use miden_client::store::AuthInfo;
+use miden_objects::accounts::AccountId;
+use crate::auth::authenticate;
+use crate::state::{ SharedAzeClient, SharedAzeNonces, SharedAzeRepository, SharedAzeSnapshots };
+
+// signs the current state of a game with its key and keeps it for the seated players
+pub async fn store_game_snapshot(
+    client: &AzeClient,
+    keystore: &AzeKeyStore,
+    snapshots: &SharedAzeSnapshots,
+    game_account_id: AccountId
+) -> Result<(), KeyStoreError> {
+    let (game_account, _) = client.get_account(game_account_id)?;
+    let snapshot = match keystore.account_auth(client, game_account_id)? {
+        AuthInfo::RpoFalcon512(game_key) => sign_game_snapshot(&game_account, &game_key),
+    };
+
+    snapshots.write().await.insert(game_account_id, snapshot);
+    Ok(())
+}
+
+#[get("/v1/game/{game_id}/snapshot")]
+pub async fn get_game_snapshot(
+    client: SharedAzeClient,
+    nonces: SharedAzeNonces,
+    repository: SharedAzeRepository,
+    snapshots: SharedAzeSnapshots,
+    game_id: Path<u64>,
+    req: HttpRequest
+) -> Result<Json<GameSnapshot>, AccountQueryError> {
+    let game_account_id = AccountId::try_from(game_id.into_inner()).map_err(
+        |_| AccountQueryError::BadQueryRequest
+    )?;
+
+    // the table state is only handed to the players seated at it
+    let player_account_id = authenticate(&client.lock().await, &nonces, &req, &[]).ok_or(
+        AccountQueryError::Unauthorized
+    )?;
+    let table = repository
+        .table(game_account_id)
+        .map_err(|_| AccountQueryError::QueryFailed)?
+        .ok_or(AccountQueryError::AccountNotFound)?;
+    if table.seat_of(player_account_id).is_none() {
+        return Err(AccountQueryError::Unauthorized);
+    }
+
+    snapshots
+        .read().await
+        .get(&game_account_id)
+        .cloned()
+        .map(Json)
+        .ok_or(AccountQueryError::AccountNotFound)
+}
@@ -0,0 +1,113 @@
+use std::time::{ SystemTime, UNIX_EPOCH };
+
+use actix_web::HttpRequest;
+use aze_lib::auth::{
+    is_request_fresh,
+    request_digest,
+    table_consent_digest,
+    verify_request_signature,
+    NonceRegistry,
+    ACCOUNT_ID_HEADER,
+    NONCE_HEADER,
+    SIGNATURE_HEADER,
+    TIMESTAMP_HEADER,
+};
+use aze_lib::client::AzeClient;
+use aze_types::accounts::GameCreationRequest;
+use miden_objects::{ accounts::AccountId, Word };
+
+fn header<'a>(req: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    req.headers().get(name)?.to_str().ok()
+}
+
+/// Checks the Falcon signature carried by a request.
+///
+/// The signature must cover the canonical digest of the method, path and query, timestamp, nonce and raw
+/// body, and verify against the public key stored in slot 0 of the signing player account. Returns
+/// the id of that account, or `None` if anything is missing, stale, invalid or already used.
+pub fn authenticate(
+    client: &AzeClient,
+    nonces: &NonceRegistry,
+    req: &HttpRequest,
+    body: &[u8]
+) -> Option<AccountId> {
+    let account_id = header(req, ACCOUNT_ID_HEADER)?.parse::<u64>().ok()?;
+    let account_id = AccountId::try_from(account_id).ok()?;
+    let timestamp = header(req, TIMESTAMP_HEADER)?.parse::<u64>().ok()?;
+    let nonce = header(req, NONCE_HEADER)?.parse::<u64>().ok()?;
+    let signature = header(req, SIGNATURE_HEADER)?;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    if !is_request_fresh(timestamp, now) {
+        return None;
+    }
+
+    let pub_key = player_pub_key(client, account_id)?;
+    // the query string is signed along with the path, nobody can change what a signed GET asks for
+    let path = req.uri().path_and_query().map_or(req.path(), |path| path.as_str());
+    let digest = request_digest(req.method().as_str(), path, timestamp, nonce, body);
+    if !verify_request_signature(pub_key, digest, signature) {
+        return None;
+    }
+
+    // only checked once the signature holds, so nobody else can burn the nonces of an account
+    nonces.register(account_id, nonce, timestamp, now).then_some(account_id)
+}
+
+/// Checks that every player of `request` but `opener` signed their consent to its terms.
+///
+/// Each consent has to verify against the key of its player, be fresh and carry a nonce that player
+/// never used, so it opens a single table. The opener agrees by signing the request itself.
+pub fn verify_table_consents(
+    client: &AzeClient,
+    nonces: &NonceRegistry,
+    request: &GameCreationRequest,
+    opener: AccountId
+) -> bool {
+    let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs()) else {
+        return false;
+    };
+
+    let mut consents = vec![];
+    for player_id in &request.player_ids {
+        if *player_id == u64::from(opener) {
+            continue;
+        }
+        let Some(consent) = request.consents.iter().find(|consent| consent.player_id == *player_id) else {
+            return false;
+        };
+        let Ok(account_id) = AccountId::try_from(*player_id) else {
+            return false;
+        };
+        let Some(pub_key) = player_pub_key(client, account_id) else {
+            return false;
+        };
+
+        let digest = table_consent_digest(
+            &request.player_ids,
+            request.faucet_id,
+            request.small_blind,
+            request.buy_in,
+            consent.timestamp,
+            consent.nonce
+        );
+        if
+            !is_request_fresh(consent.timestamp, now) ||
+            !verify_request_signature(pub_key, digest, &consent.signature)
+        {
+            return false;
+        }
+        consents.push((account_id, consent));
+    }
+
+    // nonces are only spent once every consent holds, a table refused for one player burns none
+    consents
+        .into_iter()
+        .all(|(account_id, consent)| nonces.register(account_id, consent.nonce, consent.timestamp, now))
+}
+
+// the key commitment stored in slot 0 of a player account known to the store
+fn player_pub_key(client: &AzeClient, account_id: AccountId) -> Option<Word> {
+    let (account, _) = client.get_account(account_id).ok()?;
+    Some(account.storage().get_item(0).into())
+}
@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+
+use aze_lib::constants::{ MAX_PLAYERS, MIN_PLAYERS };
+use clap::Parser;
+use figment::{ providers::{ Env, Format, Serialized, Toml }, Figment };
+use miden_client::config::{ ClientConfig, RpcConfig, StoreConfig };
+use serde::{ Deserialize, Serialize };
+
+const CONFIG_FILE_NAME: &str = "aze-server.toml";
+const ENV_PREFIX: &str = "AZE_SERVER_";
+
+/// Settings of the server.
+///
+/// Read from the defaults below, then `aze-server.toml`, then `AZE_SERVER_*` environment
+/// variables and finally the command-line flags, each overriding the previous one. Nested keys
+/// are separated by `__` in the environment, e.g. `AZE_SERVER_RPC__ENDPOINT__HOST`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    // filter handed to env_logger, e.g. `info` or `aze_server=debug,actix_web=info`
+    pub log_level: String,
+    // database of the tables recorded by the server, see `AzeRepository`
+    pub repository_path: PathBuf,
+    // players a lobby queue waits for before opening a table for them
+    pub table_seats: u8,
+    // directory of the encrypted game account keys, see `AzeKeyStore`
+    pub keystore_path: PathBuf,
+    // passphrase the keys are encrypted with, best left to `AZE_SERVER_KEYSTORE_PASSPHRASE`
+    #[serde(skip_serializing)]
+    pub keystore_passphrase: Option<String>,
+    // store and node of the shared client, under the same keys as in `miden-client.toml`
+    pub store: StoreConfig,
+    pub rpc: RpcConfig,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 8000,
+            log_level: "info".to_string(),
+            repository_path: PathBuf::from("aze-server.sqlite3"),
+            table_seats: MIN_PLAYERS,
+            keystore_path: PathBuf::from("aze-server-keys"),
+            keystore_passphrase: None,
+            store: StoreConfig {
+                database_filepath: "store.sqlite3".to_string(),
+            },
+            rpc: RpcConfig::default(),
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn client_config(&self) -> ClientConfig {
+        ClientConfig::new(self.store.clone(), self.rpc.clone())
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "aze-server", about = "Hosts aze poker tables over http")]
+pub struct ServerArgs {
+    /// Config file, missing keys and a missing file fall back to the defaults
+    #[arg(long, env = "AZE_SERVER_CONFIG", default_value = CONFIG_FILE_NAME)]
+    config: PathBuf,
+    /// Address to bind to
+    #[arg(long)]
+    host: Option<String>,
+    #[arg(long)]
+    port: Option<u16>,
+    #[arg(long)]
+    log_level: Option<String>,
+    #[arg(long)]
+    repository_path: Option<PathBuf>,
+    #[arg(long)]
+    table_seats: Option<u8>,
+    #[arg(long)]
+    keystore_path: Option<PathBuf>,
+    /// Sqlite file of the client store
+    #[arg(long)]
+    store_path: Option<String>,
+    /// Host of the miden node
+    #[arg(long)]
+    rpc_host: Option<String>,
+    #[arg(long)]
+    rpc_port: Option<u16>,
+}
+
+impl ServerArgs {
+    pub fn load_config(&self) -> Result<ServerConfig, String> {
+        let figment = Figment::from(Serialized::defaults(ServerConfig::default()))
+            .merge(Toml::file(&self.config))
+            .merge(Env::prefixed(ENV_PREFIX).split("__"));
+
+        // only the flags that were passed override the config
+        let figment = merge_flag(figment, "host", self.host.as_ref());
+        let figment = merge_flag(figment, "port", self.port);
+        let figment = merge_flag(figment, "log_level", self.log_level.as_ref());
+        let figment = merge_flag(figment, "repository_path", self.repository_path.as_ref());
+        let figment = merge_flag(figment, "table_seats", self.table_seats);
+        let figment = merge_flag(figment, "keystore_path", self.keystore_path.as_ref());
+        let figment = merge_flag(figment, "store.database_filepath", self.store_path.as_ref());
+        let figment = merge_flag(figment, "rpc.endpoint.host", self.rpc_host.as_ref());
+        let figment = merge_flag(figment, "rpc.endpoint.port", self.rpc_port);
+
+        let config: ServerConfig = figment
+            .extract()
+            .map_err(|err| format!("Failed to load {} config file: {err}", self.config.display()))?;
+
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&config.table_seats) {
+            return Err(
+                format!("table_seats must be between {MIN_PLAYERS} and {MAX_PLAYERS}, got {}", config.table_seats)
+            );
+        }
+        if config.keystore_passphrase.is_none() {
+            return Err(
+                format!("the game keys are encrypted, set {ENV_PREFIX}KEYSTORE_PASSPHRASE")
+            );
+        }
+        Ok(config)
+    }
+}
+
+// `key` is a dotted path into the config
+fn merge_flag<T: Serialize>(figment: Figment, key: &str, value: Option<T>) -> Figment {
+    match value {
+        Some(value) => figment.merge(Serialized::default(key, value)),
+        None => figment,
+    }
+}
@@ -1,26 +1,100 @@
 mod api;
+mod auth;
+mod config;
+mod repository;
+mod state;
+mod sync;
 use api::{
-    accounts::{ create_aze_game_account, create_aze_player_account },
-    action::{ aze_poker_game_action, aze_poker_game_call, aze_poker_game_fold, aze_poker_game_check },
+    accounts::{ create_aze_game, create_aze_player_account },
+    action::{ aze_poker_game_action, aze_poker_game_action_request, aze_submit_proven_transaction },
+    events::aze_game_events,
+    inbox::{ deliver_private_note, get_private_notes },
+    lobby::{ get_lobby, get_queue_status, join_queue, leave_queue },
+    query::{ get_aze_game, get_aze_game_history, get_aze_game_notes, get_aze_games, get_aze_player },
+    snapshots::get_game_snapshot,
 };
-use actix_web::{ HttpServer, App, middleware::Logger };
+use actix_web::{ HttpServer, App, middleware::Logger, web::Data };
+use aze_lib::auth::NonceRegistry;
+use aze_lib::client::create_aze_client_with_config;
+use aze_lib::keystore::AzeKeyStore;
+use clap::Parser;
+use config::ServerArgs;
+use repository::AzeRepository;
+use state::{
+    AzeLobby,
+    AzePendingActions,
+    AzeSubscriptions,
+    SharedAzeLobby,
+    SharedAzeKeyStore,
+    SharedAzeNonces,
+    SharedAzePendingActions,
+    SharedAzeRepository,
+    SharedAzeSnapshots,
+    SharedAzeSubscriptions,
+};
+use tokio::sync::Mutex;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    // std::env::set_var("RUST_LOG", "debug");
-    // std::env::set_var("RUST_BACKTRACE", "1");
-    // env_logger::init();
+    let config = ServerArgs::parse().load_config().map_err(std::io::Error::other)?;
+    env_logger::Builder::new().parse_filters(&config.log_level).init();
+
+    // a single client is shared by every worker, handlers take turns on it through the mutex
+    let client = Data::new(Mutex::new(create_aze_client_with_config(&config.client_config())));
+    let repository: SharedAzeRepository = Data::new(
+        AzeRepository::open(&config.repository_path).map_err(std::io::Error::other)?
+    );
+    // game keys only ever live here, the client store keeps an unrelated key for every game account
+    let keystore: SharedAzeKeyStore = Data::new(
+        AzeKeyStore::open(
+            &config.keystore_path,
+            config.keystore_passphrase.as_deref().unwrap_or_default()
+        ).map_err(std::io::Error::other)?
+    );
+    let nonces: SharedAzeNonces = Data::new(NonceRegistry::default());
+    let pending_actions: SharedAzePendingActions = Data::new(AzePendingActions::default());
+    let snapshots: SharedAzeSnapshots = Data::new(Default::default());
+    let subscriptions: SharedAzeSubscriptions = Data::new(AzeSubscriptions::default());
+    // queues and seats are kept in the repository, a restart finds them as they were
+    let lobby: SharedAzeLobby = Data::new(
+        AzeLobby::open(config.table_seats, repository.clone()).map_err(std::io::Error::other)?
+    );
+
+    // table updates are pushed by this task as it syncs, never by the request handlers
+    actix_web::rt::spawn(
+        sync::sync_game_updates(client.clone(), repository.clone(), subscriptions.clone())
+    );
 
     HttpServer::new(move || {
-        let _ = Logger::default();
         App::new()
-            .service(create_aze_game_account)
+            .wrap(Logger::default())
+            .app_data(client.clone())
+            .app_data(repository.clone())
+            .app_data(keystore.clone())
+            .app_data(nonces.clone())
+            .app_data(pending_actions.clone())
+            .app_data(snapshots.clone())
+            .app_data(subscriptions.clone())
+            .app_data(lobby.clone())
+            .service(create_aze_game)
             .service(create_aze_player_account)
             .service(aze_poker_game_action)
-            .service(aze_poker_game_call)
-            .service(aze_poker_game_fold)
-            .service(aze_poker_game_check)
+            .service(aze_poker_game_action_request)
+            .service(aze_submit_proven_transaction)
+            .service(aze_game_events)
+            .service(get_aze_games)
+            .service(get_aze_game)
+            .service(get_aze_game_history)
+            .service(get_aze_game_notes)
+            .service(get_aze_player)
+            .service(deliver_private_note)
+            .service(get_private_notes)
+            .service(get_game_snapshot)
+            .service(get_lobby)
+            .service(join_queue)
+            .service(get_queue_status)
+            .service(leave_queue)
     })
-        .bind(("127.0.0.1", 8000))?
+        .bind((config.host.as_str(), config.port))?
         .run().await
 }
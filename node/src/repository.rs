@@ -0,0 +1,644 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use aze_lib::bots::is_hand_over;
+use aze_types::actions::{ ActionRecord, GameState, PokerAction };
+use aze_types::lobby::{ LobbyTable, StakeLevel };
+use miden_objects::{
+    accounts::AccountId,
+    notes::{ Note, NoteId },
+    utils::{ Deserializable, Serializable },
+};
+use rusqlite::{ params, types::Type, Connection, Transaction };
+
+use crate::state::AzeTable;
+
+// account ids are u64s, they are stored bit for bit in sqlite's signed integers
+const TABLES: &str =
+    "
+    CREATE TABLE IF NOT EXISTS games (
+        game_id INTEGER PRIMARY KEY,
+        faucet_id INTEGER NOT NULL,
+        small_blind INTEGER NOT NULL,
+        buy_in INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS seats (
+        game_id INTEGER NOT NULL REFERENCES games (game_id),
+        seat INTEGER NOT NULL,
+        player_id INTEGER NOT NULL,
+        PRIMARY KEY (game_id, seat)
+    );
+    CREATE TABLE IF NOT EXISTS hands (
+        game_id INTEGER NOT NULL REFERENCES games (game_id),
+        hand_number INTEGER NOT NULL,
+        phase INTEGER NOT NULL,
+        finished INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (game_id, hand_number)
+    );
+    CREATE TABLE IF NOT EXISTS actions (
+        action_id INTEGER PRIMARY KEY AUTOINCREMENT,
+        game_id INTEGER NOT NULL,
+        hand_number INTEGER NOT NULL,
+        seat INTEGER NOT NULL,
+        player_id INTEGER NOT NULL,
+        action TEXT NOT NULL,
+        amount INTEGER,
+        note_id TEXT NOT NULL,
+        FOREIGN KEY (game_id, hand_number) REFERENCES hands (game_id, hand_number)
+    );
+    CREATE TABLE IF NOT EXISTS inbox (
+        note_id TEXT PRIMARY KEY,
+        recipient_id INTEGER NOT NULL,
+        note BLOB NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS lobby_queue (
+        faucet_id INTEGER NOT NULL,
+        small_blind INTEGER NOT NULL,
+        buy_in INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        player_id INTEGER NOT NULL UNIQUE,
+        PRIMARY KEY (faucet_id, small_blind, buy_in, position)
+    );
+    CREATE TABLE IF NOT EXISTS lobby_seated (
+        player_id INTEGER PRIMARY KEY,
+        game_id INTEGER NOT NULL REFERENCES games (game_id)
+    );
+";
+
+type Migration = fn(&Transaction) -> rusqlite::Result<()>;
+
+// each one moves the schema up a version, the version a database is at is kept in its `user_version`
+const MIGRATIONS: [Migration; 1] = [create_tables];
+
+/// Action a player sent to a table, recorded once the game account consumed its note.
+#[derive(Clone, Copy, Debug)]
+pub struct TableAction {
+    pub seat: u8,
+    pub player_id: AccountId,
+    pub action: PokerAction,
+    // only set for bets and raises
+    pub amount: Option<u8>,
+}
+
+/// Registry of the tables opened through this server, in a sqlite database of its own.
+///
+/// A game is written along with its seats and first hand when the table is opened. Each action
+/// is added once the transaction consuming its note is committed, and the hand follows the phase
+/// the table moved to until it is over. The miden store only holds opaque accounts, so this is where the handlers
+/// look tables up, and what keeps them across restarts. Private notes waiting in an inbox are kept here
+/// as well, until they are consumed on chain, and so are the lobby queues and the players they seated.
+/// Databases of earlier versions are migrated when opened.
+pub struct AzeRepository {
+    connection: Mutex<Connection>,
+}
+
+impl AzeRepository {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    fn with_connection(mut connection: Connection) -> rusqlite::Result<Self> {
+        migrate(&mut connection)?;
+        Ok(Self { connection: Mutex::new(connection) })
+    }
+
+    pub fn insert_game(
+        &self,
+        game_id: AccountId,
+        table: &AzeTable,
+        small_blind: u8,
+        buy_in: u8,
+        inbox_notes: &[(AccountId, &Note)]
+    ) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO games (game_id, faucet_id, small_blind, buy_in) VALUES (?1, ?2, ?3, ?4)",
+            params![to_sql_id(game_id), to_sql_id(table.faucet_id), small_blind, buy_in]
+        )?;
+        for (seat, player_id) in table.player_ids.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO seats (game_id, seat, player_id) VALUES (?1, ?2, ?3)",
+                params![to_sql_id(game_id), seat as u8, to_sql_id(*player_id)]
+            )?;
+        }
+        transaction.execute(
+            "INSERT INTO hands (game_id, hand_number, phase) VALUES (?1, 1, 0)",
+            params![to_sql_id(game_id)]
+        )?;
+        for (recipient_id, note) in inbox_notes {
+            insert_inbox_note(&transaction, *recipient_id, note)?;
+        }
+
+        transaction.commit()
+    }
+
+    pub fn tables(&self) -> rusqlite::Result<Vec<(AccountId, AzeTable)>> {
+        query_tables(&self.connection.lock().unwrap(), None)
+    }
+
+    pub fn table(&self, game_id: AccountId) -> rusqlite::Result<Option<AzeTable>> {
+        let tables = query_tables(&self.connection.lock().unwrap(), Some(game_id))?;
+        Ok(tables.into_iter().next().map(|(_, table)| table))
+    }
+
+    /// Adds an action to the current hand of a table, and moves the hand to the phase of `game_state`,
+    /// marking it finished once the hand is over.
+    pub fn record_action(
+        &self,
+        game_id: AccountId,
+        action: &TableAction,
+        note_id: NoteId,
+        game_state: &GameState
+    ) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        let hand_number = current_hand(&transaction, game_id)?;
+
+        transaction.execute(
+            "INSERT INTO actions (game_id, hand_number, seat, player_id, action, amount, note_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                to_sql_id(game_id),
+                hand_number,
+                action.seat,
+                to_sql_id(action.player_id),
+                action_name(action.action),
+                action.amount,
+                note_id.to_hex()
+            ]
+        )?;
+        transaction.execute(
+            "UPDATE hands SET phase = ?3, finished = ?4 WHERE game_id = ?1 AND hand_number = ?2",
+            params![to_sql_id(game_id), hand_number, game_state.current_phase, is_hand_over(game_state)]
+        )?;
+
+        transaction.commit()
+    }
+
+    /// Tables whose current hand is still being played, with their stakes and player count.
+    pub fn open_tables(&self) -> rusqlite::Result<Vec<LobbyTable>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT games.game_id, games.faucet_id, games.small_blind, games.buy_in,
+                (SELECT COUNT(*) FROM seats WHERE seats.game_id = games.game_id)
+            FROM games
+            JOIN hands ON hands.game_id = games.game_id AND hands.hand_number = (
+                SELECT MAX(hand_number) FROM hands WHERE hands.game_id = games.game_id
+            )
+            WHERE hands.finished = 0
+            ORDER BY games.game_id"
+        )?;
+
+        let tables = statement.query_map([], |row| {
+            Ok(LobbyTable {
+                game_id: row.get::<_, i64>(0)? as u64,
+                stakes: StakeLevel {
+                    faucet_id: row.get::<_, i64>(1)? as u64,
+                    small_blind: row.get(2)?,
+                    buy_in: row.get(3)?,
+                },
+                player_count: row.get(4)?,
+            })
+        })?;
+        tables.collect()
+    }
+
+    pub fn actions(&self, game_id: AccountId) -> rusqlite::Result<Vec<ActionRecord>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT hand_number, seat, player_id, action, amount, note_id FROM actions
+            WHERE game_id = ?1 ORDER BY action_id"
+        )?;
+
+        let actions = statement.query_map(params![to_sql_id(game_id)], |row| {
+            let action: String = row.get(3)?;
+            Ok(ActionRecord {
+                hand_number: row.get(0)?,
+                seat: row.get(1)?,
+                player_id: row.get::<_, i64>(2)? as u64,
+                action: parse_action(&action).ok_or(
+                    rusqlite::Error::FromSqlConversionFailure(
+                        3,
+                        Type::Text,
+                        format!("unknown action {action}").into()
+                    )
+                )?,
+                amount: row.get(4)?,
+                note_id: row.get(5)?,
+            })
+        })?;
+        actions.collect()
+    }
+
+    /// Leaves a private note in the inbox of its recipient, delivering the same note again changes nothing.
+    pub fn insert_inbox_note(&self, recipient_id: AccountId, note: &Note) -> rusqlite::Result<()> {
+        insert_inbox_note(&self.connection.lock().unwrap(), recipient_id, note)
+    }
+
+    /// Notes waiting for `recipient_id`, in the order they were delivered.
+    pub fn inbox(&self, recipient_id: AccountId) -> rusqlite::Result<Vec<Note>> {
+        query_inbox(&self.connection.lock().unwrap(), Some(recipient_id))
+    }
+
+    /// Notes waiting in every inbox.
+    pub fn inbox_notes(&self) -> rusqlite::Result<Vec<Note>> {
+        query_inbox(&self.connection.lock().unwrap(), None)
+    }
+
+    pub fn remove_inbox_notes(&self, note_ids: &[NoteId]) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        for note_id in note_ids {
+            transaction.execute("DELETE FROM inbox WHERE note_id = ?1", params![note_id.to_hex()])?;
+        }
+        transaction.commit()
+    }
+
+    /// Players waiting at each stake level, in queue order.
+    pub fn lobby_queues(&self) -> rusqlite::Result<BTreeMap<StakeLevel, Vec<AccountId>>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT faucet_id, small_blind, buy_in, player_id FROM lobby_queue
+            ORDER BY faucet_id, small_blind, buy_in, position"
+        )?;
+        let rows = statement.query_map([], |row| {
+            let stakes = StakeLevel {
+                faucet_id: row.get::<_, i64>(0)? as u64,
+                small_blind: row.get(1)?,
+                buy_in: row.get(2)?,
+            };
+            Ok((stakes, from_sql_id(row.get(3)?, 3)?))
+        })?;
+
+        let mut queues: BTreeMap<StakeLevel, Vec<AccountId>> = BTreeMap::new();
+        for row in rows {
+            let (stakes, player_id) = row?;
+            queues.entry(stakes).or_default().push(player_id);
+        }
+        Ok(queues)
+    }
+
+    /// Replaces the queue of `stakes` with `player_ids`, an empty queue is removed.
+    pub fn save_lobby_queue(&self, stakes: StakeLevel, player_ids: &[AccountId]) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "DELETE FROM lobby_queue WHERE faucet_id = ?1 AND small_blind = ?2 AND buy_in = ?3",
+            params![stakes.faucet_id as i64, stakes.small_blind, stakes.buy_in]
+        )?;
+        for (position, player_id) in player_ids.iter().enumerate() {
+            transaction.execute(
+                "INSERT INTO lobby_queue (faucet_id, small_blind, buy_in, position, player_id)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    stakes.faucet_id as i64,
+                    stakes.small_blind,
+                    stakes.buy_in,
+                    position as u32,
+                    to_sql_id(*player_id)
+                ]
+            )?;
+        }
+
+        transaction.commit()
+    }
+
+    /// Game each player was last seated at by a lobby queue.
+    pub fn lobby_seated(&self) -> rusqlite::Result<BTreeMap<AccountId, AccountId>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare("SELECT player_id, game_id FROM lobby_seated")?;
+        let rows = statement.query_map([], |row| {
+            Ok((from_sql_id(row.get(0)?, 0)?, from_sql_id(row.get(1)?, 1)?))
+        })?;
+        rows.collect()
+    }
+
+    pub fn seat_lobby_players(&self, player_ids: &[AccountId], game_id: AccountId) -> rusqlite::Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        for player_id in player_ids {
+            transaction.execute(
+                "INSERT OR REPLACE INTO lobby_seated (player_id, game_id) VALUES (?1, ?2)",
+                params![to_sql_id(*player_id), to_sql_id(game_id)]
+            )?;
+        }
+        transaction.commit()
+    }
+
+    pub fn unseat_lobby_player(&self, player_id: AccountId) -> rusqlite::Result<()> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM lobby_seated WHERE player_id = ?1", params![to_sql_id(player_id)])?;
+        Ok(())
+    }
+}
+
+// applies the migrations the database is missing, all of them in a single transaction
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let transaction = connection.transaction()?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(&transaction)?;
+        transaction.pragma_update(None, "user_version", (index + 1) as i64)?;
+    }
+    transaction.commit()
+}
+
+fn create_tables(transaction: &Transaction) -> rusqlite::Result<()> {
+    transaction.execute_batch(TABLES)
+}
+
+fn insert_inbox_note(connection: &Connection, recipient_id: AccountId, note: &Note) -> rusqlite::Result<()> {
+    connection.execute(
+        "INSERT OR IGNORE INTO inbox (note_id, recipient_id, note) VALUES (?1, ?2, ?3)",
+        params![note.id().to_hex(), to_sql_id(recipient_id), note.to_bytes()]
+    )?;
+    Ok(())
+}
+
+// notes in delivery order, of every inbox or of the one of `recipient_id`
+fn query_inbox(connection: &Connection, recipient_id: Option<AccountId>) -> rusqlite::Result<Vec<Note>> {
+    let mut statement = connection.prepare(
+        "SELECT note FROM inbox WHERE ?1 IS NULL OR recipient_id = ?1 ORDER BY rowid"
+    )?;
+    let notes = statement.query_map(params![recipient_id.map(to_sql_id)], |row| {
+        let bytes: Vec<u8> = row.get(0)?;
+        Note::read_from_bytes(&bytes).map_err(|err| {
+            rusqlite::Error::FromSqlConversionFailure(0, Type::Blob, format!("{err:?}").into())
+        })
+    })?;
+    notes.collect()
+}
+
+// tables in game id order with their players in seat order, all of them or the one of `game_id`
+fn query_tables(
+    connection: &Connection,
+    game_id: Option<AccountId>
+) -> rusqlite::Result<Vec<(AccountId, AzeTable)>> {
+    let mut statement = connection.prepare(
+        "SELECT games.game_id, games.faucet_id, seats.player_id FROM games
+        JOIN seats ON seats.game_id = games.game_id
+        WHERE ?1 IS NULL OR games.game_id = ?1
+        ORDER BY games.game_id, seats.seat"
+    )?;
+    let rows = statement.query_map(params![game_id.map(to_sql_id)], |row| {
+        Ok((from_sql_id(row.get(0)?, 0)?, from_sql_id(row.get(1)?, 1)?, from_sql_id(row.get(2)?, 2)?))
+    })?;
+
+    let mut tables: Vec<(AccountId, AzeTable)> = vec![];
+    for row in rows {
+        let (game_id, faucet_id, player_id) = row?;
+        match tables.last_mut() {
+            Some((last_game_id, table)) if *last_game_id == game_id => table.player_ids.push(player_id),
+            _ => tables.push((game_id, AzeTable { player_ids: vec![player_id], faucet_id })),
+        }
+    }
+    Ok(tables)
+}
+
+// the game contract only plays a single hand for now, so the current one is the last started
+fn current_hand(transaction: &Transaction, game_id: AccountId) -> rusqlite::Result<u32> {
+    transaction.query_row(
+        "SELECT hand_number FROM hands WHERE game_id = ?1 ORDER BY hand_number DESC LIMIT 1",
+        params![to_sql_id(game_id)],
+        |row| row.get(0)
+    )
+}
+
+fn to_sql_id(account_id: AccountId) -> i64 {
+    u64::from(account_id) as i64
+}
+
+fn from_sql_id(id: i64, column: usize) -> rusqlite::Result<AccountId> {
+    AccountId::try_from(id as u64).map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(column, Type::Integer, format!("{err:?}").into())
+    })
+}
+
+fn action_name(action: PokerAction) -> &'static str {
+    match action {
+        PokerAction::Bet => "bet",
+        PokerAction::Raise => "raise",
+        PokerAction::Call => "call",
+        PokerAction::Fold => "fold",
+        PokerAction::Check => "check",
+    }
+}
+
+fn parse_action(name: &str) -> Option<PokerAction> {
+    match name {
+        "bet" => Some(PokerAction::Bet),
+        "raise" => Some(PokerAction::Raise),
+        "call" => Some(PokerAction::Call),
+        "fold" => Some(PokerAction::Fold),
+        "check" => Some(PokerAction::Check),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aze_lib::constants::{ PLAYER_INITIAL_BALANCE, SMALL_BLIND_AMOUNT };
+    use aze_types::actions::PlayerState;
+    use miden_objects::{ Digest, Felt };
+
+    use super::*;
+
+    const GAME_ID: u64 = 0x8000_0000_0000_0100;
+    const OTHER_GAME_ID: u64 = 0x8000_0000_0000_0200;
+    const FAUCET_ID: u64 = 0x8000_0000_0000_0300;
+    const PLAYER_ID: u64 = 0x8000_0000_0000_0001;
+    const OTHER_PLAYER_ID: u64 = 0x8000_0000_0000_0002;
+
+    // ids of off-chain regular accounts, the high bits carry the storage and account type
+    fn account_id(id: u64) -> AccountId {
+        AccountId::try_from(id).unwrap()
+    }
+
+    fn repository() -> AzeRepository {
+        AzeRepository::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn table(player_ids: &[u64]) -> AzeTable {
+        AzeTable {
+            player_ids: player_ids.iter().map(|id| account_id(*id)).collect(),
+            faucet_id: account_id(FAUCET_ID),
+        }
+    }
+
+    fn note_id(n: u64) -> NoteId {
+        NoteId::new(Digest::new([Felt::new(n); 4]), Digest::new([Felt::new(0); 4]))
+    }
+
+    // pre-flop heads-up table with seat 0 to act and nothing bet yet
+    fn new_hand() -> GameState {
+        GameState {
+            small_blind: SMALL_BLIND_AMOUNT,
+            big_blind: SMALL_BLIND_AMOUNT * 2,
+            buy_in: PLAYER_INITIAL_BALANCE,
+            player_count: 2,
+            current_turn: 0,
+            raiser: None,
+            highest_bet: 0,
+            current_phase: 0,
+            check_count: 0,
+            players: (0..2)
+                .map(|seat| PlayerState {
+                    seat,
+                    bet: 0,
+                    balance: PLAYER_INITIAL_BALANCE,
+                    has_folded: false,
+                })
+                .collect(),
+            community_cards: vec![],
+        }
+    }
+
+    fn user_version(repository: &AzeRepository) -> i64 {
+        repository.connection
+            .lock()
+            .unwrap()
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn player_ids(table: &AzeTable) -> Vec<u64> {
+        table.player_ids.iter().map(|id| u64::from(*id)).collect()
+    }
+
+    #[test]
+    fn test_tables_are_read_back_in_seat_order() {
+        let repository = repository();
+        let game_id = account_id(GAME_ID);
+        let other_game_id = account_id(OTHER_GAME_ID);
+        repository.insert_game(other_game_id, &table(&[OTHER_PLAYER_ID, PLAYER_ID]), 5, 100, &[]).unwrap();
+        repository.insert_game(game_id, &table(&[PLAYER_ID, OTHER_PLAYER_ID]), 5, 100, &[]).unwrap();
+
+        let tables = repository.tables().unwrap();
+        let game_ids: Vec<u64> = tables
+            .iter()
+            .map(|(game_id, _)| u64::from(*game_id))
+            .collect();
+        assert_eq!(game_ids, vec![GAME_ID, OTHER_GAME_ID]);
+        assert_eq!(player_ids(&tables[0].1), vec![PLAYER_ID, OTHER_PLAYER_ID]);
+        assert_eq!(player_ids(&tables[1].1), vec![OTHER_PLAYER_ID, PLAYER_ID]);
+
+        let other_table = repository.table(other_game_id).unwrap().unwrap();
+        assert_eq!(player_ids(&other_table), vec![OTHER_PLAYER_ID, PLAYER_ID]);
+        assert_eq!(u64::from(other_table.faucet_id), FAUCET_ID);
+        assert!(repository.table(account_id(0x8000_0000_0000_0400)).unwrap().is_none());
+
+        // a game account is only opened once, the failed insert leaves the table as it was
+        assert!(repository.insert_game(game_id, &table(&[OTHER_PLAYER_ID]), 5, 100, &[]).is_err());
+        let table = repository.table(game_id).unwrap().unwrap();
+        assert_eq!(player_ids(&table), vec![PLAYER_ID, OTHER_PLAYER_ID]);
+    }
+
+    #[test]
+    fn test_actions_are_recorded_in_the_order_they_were_played() {
+        let repository = repository();
+        let game_id = account_id(GAME_ID);
+        repository.insert_game(game_id, &table(&[PLAYER_ID, OTHER_PLAYER_ID]), 5, 100, &[]).unwrap();
+
+        let mut game_state = new_hand();
+        game_state.highest_bet = 10;
+        let bet = TableAction {
+            seat: 0,
+            player_id: account_id(PLAYER_ID),
+            action: PokerAction::Bet,
+            amount: Some(10),
+        };
+        repository.record_action(game_id, &bet, note_id(1), &game_state).unwrap();
+
+        let call = TableAction {
+            seat: 1,
+            player_id: account_id(OTHER_PLAYER_ID),
+            action: PokerAction::Call,
+            amount: None,
+        };
+        game_state.current_phase = 1;
+        repository.record_action(game_id, &call, note_id(2), &game_state).unwrap();
+
+        assert_eq!(repository.actions(game_id).unwrap(), vec![
+            ActionRecord {
+                hand_number: 1,
+                seat: 0,
+                player_id: PLAYER_ID,
+                action: PokerAction::Bet,
+                amount: Some(10),
+                note_id: note_id(1).to_hex(),
+            },
+            ActionRecord {
+                hand_number: 1,
+                seat: 1,
+                player_id: OTHER_PLAYER_ID,
+                action: PokerAction::Call,
+                amount: None,
+                note_id: note_id(2).to_hex(),
+            },
+        ]);
+        assert_eq!(repository.actions(account_id(OTHER_GAME_ID)).unwrap(), vec![]);
+
+        // the hand follows the table and the lobby lists it until it is over
+        assert_eq!(repository.open_tables().unwrap(), vec![LobbyTable {
+            game_id: GAME_ID,
+            stakes: StakeLevel { faucet_id: FAUCET_ID, small_blind: 5, buy_in: 100 },
+            player_count: 2,
+        }]);
+        let fold = TableAction {
+            seat: 0,
+            player_id: account_id(PLAYER_ID),
+            action: PokerAction::Fold,
+            amount: None,
+        };
+        game_state.players[0].has_folded = true;
+        repository.record_action(game_id, &fold, note_id(3), &game_state).unwrap();
+        assert_eq!(repository.open_tables().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_actions_of_unknown_tables_are_not_recorded() {
+        let repository = repository();
+        let check = TableAction {
+            seat: 0,
+            player_id: account_id(PLAYER_ID),
+            action: PokerAction::Check,
+            amount: None,
+        };
+
+        assert!(repository.record_action(account_id(GAME_ID), &check, note_id(1), &new_hand()).is_err());
+        assert_eq!(repository.actions(account_id(GAME_ID)).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_new_database_is_at_the_latest_version() {
+        assert_eq!(user_version(&repository()), MIGRATIONS.len() as i64);
+    }
+
+    #[test]
+    fn test_lobby_queues_and_seats_are_read_back() {
+        let repository = repository();
+        let stakes = StakeLevel { faucet_id: FAUCET_ID, small_blind: 5, buy_in: 100 };
+        let other_stakes = StakeLevel { faucet_id: FAUCET_ID, small_blind: 10, buy_in: 100 };
+        repository.save_lobby_queue(stakes, &[account_id(OTHER_PLAYER_ID), account_id(PLAYER_ID)]).unwrap();
+        repository.save_lobby_queue(other_stakes, &[account_id(0x8000_0000_0000_0003)]).unwrap();
+
+        // a queue is saved as a whole, emptying it leaves the other stakes alone
+        repository.save_lobby_queue(other_stakes, &[]).unwrap();
+        assert_eq!(
+            repository.lobby_queues().unwrap(),
+            BTreeMap::from([(stakes, vec![account_id(OTHER_PLAYER_ID), account_id(PLAYER_ID)])])
+        );
+
+        let game_id = account_id(GAME_ID);
+        repository.insert_game(game_id, &table(&[PLAYER_ID, OTHER_PLAYER_ID]), 5, 100, &[]).unwrap();
+        let player_ids = [account_id(PLAYER_ID), account_id(OTHER_PLAYER_ID)];
+        repository.seat_lobby_players(&player_ids, game_id).unwrap();
+        repository.unseat_lobby_player(account_id(OTHER_PLAYER_ID)).unwrap();
+        assert_eq!(repository.lobby_seated().unwrap(), BTreeMap::from([(account_id(PLAYER_ID), game_id)]));
+    }
+}
@@ -0,0 +1,414 @@
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use std::time::{ Duration, Instant };
+
+use actix_web::web::Data;
+use aze_lib::auth::NonceRegistry;
+use aze_lib::client::AzeClient;
+use aze_lib::keystore::AzeKeyStore;
+use aze_lib::storage::diff_game_state;
+use aze_types::accounts::GameSnapshot;
+use aze_types::actions::GameState;
+use aze_types::events::GameEvent;
+use aze_types::lobby::{ QueueStatus, StakeLevel };
+use miden_objects::{ accounts::AccountId, notes::Note, Digest };
+use tokio::sync::{ broadcast, Mutex, RwLock as AsyncRwLock };
+
+use crate::repository::{ AzeRepository, TableAction };
+
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+// time a player has to prove an action handed out to them, the table may have moved on by then anyway
+const PENDING_ACTION_TTL: Duration = Duration::from_secs(600);
+
+/// Long-lived client shared across requests.
+///
+/// `AzeClient` owns the sqlite store and the rpc connection, so it is created once at startup
+/// and every handler locks it for the duration of its transactions instead of building a new one.
+pub type SharedAzeClient = Data<Mutex<AzeClient>>;
+
+/// Seating of a table created through this server.
+#[derive(Clone)]
+pub struct AzeTable {
+    /// Player account ids in seat order.
+    pub player_ids: Vec<AccountId>,
+    /// Faucet of the asset the table is played with.
+    pub faucet_id: AccountId,
+}
+
+impl AzeTable {
+    pub fn seat_of(&self, player_id: AccountId) -> Option<u8> {
+        self.player_ids
+            .iter()
+            .position(|id| *id == player_id)
+            .map(|seat| seat as u8)
+    }
+}
+
+/// Encrypted keys of the game accounts opened by the server.
+pub type SharedAzeKeyStore = Data<AzeKeyStore>;
+
+/// Nonces of the signed requests accepted by `authenticate`, so none of them can be replayed.
+pub type SharedAzeNonces = Data<NonceRegistry>;
+
+/// Tables, hands and actions recorded by the server.
+pub type SharedAzeRepository = Data<AzeRepository>;
+
+/// Notes expected from actions handed out for client-side proving, keyed by game and player account ids.
+///
+/// Only the transaction that creates the expected note is relayed for that player. The whole note is
+/// kept since a private one can't be fetched from the node once committed, along with the action it
+/// plays so it can be recorded once consumed. Actions not proven within `PENDING_ACTION_TTL` are dropped.
+#[derive(Default)]
+pub struct AzePendingActions {
+    actions: Mutex<BTreeMap<(AccountId, AccountId), PendingAction>>,
+}
+
+struct PendingAction {
+    note: Note,
+    action: TableAction,
+    handed_out_at: Instant,
+}
+
+impl AzePendingActions {
+    /// Records the action handed out to a player at a table, replacing the one they did not prove.
+    pub async fn insert(
+        &self,
+        game_id: AccountId,
+        player_id: AccountId,
+        note: Note,
+        action: TableAction,
+        now: Instant
+    ) {
+        let mut actions = self.actions.lock().await;
+        actions.retain(|_, pending| !is_pending_action_stale(pending, now));
+        actions.insert((game_id, player_id), PendingAction { note, action, handed_out_at: now });
+    }
+
+    /// Takes the action handed out to a player at a table, if it is still pending.
+    pub async fn take(
+        &self,
+        game_id: AccountId,
+        player_id: AccountId,
+        now: Instant
+    ) -> Option<(Note, TableAction)> {
+        let mut actions = self.actions.lock().await;
+        actions.retain(|_, pending| !is_pending_action_stale(pending, now));
+        actions.remove(&(game_id, player_id)).map(|pending| (pending.note, pending.action))
+    }
+}
+
+fn is_pending_action_stale(pending: &PendingAction, now: Instant) -> bool {
+    now.saturating_duration_since(pending.handed_out_at) > PENDING_ACTION_TTL
+}
+
+pub type SharedAzePendingActions = Data<AzePendingActions>;
+
+/// Latest signed snapshot of every table, keyed by game account id.
+///
+/// Game accounts are off-chain, so this is how seated players get to see the state behind the
+/// commitment recorded on chain. A snapshot replaces the previous one after every action.
+pub type SharedAzeSnapshots = Data<AsyncRwLock<BTreeMap<AccountId, GameSnapshot>>>;
+
+/// Broadcast channels of the tables that currently have connected players or spectators.
+#[derive(Default)]
+pub struct AzeSubscriptions {
+    channels: RwLock<BTreeMap<AccountId, GameChannel>>,
+}
+
+// subscribers of a table, with the account hash and state their events were last computed from
+struct GameChannel {
+    sender: broadcast::Sender<GameEvent>,
+    last_seen: (Digest, GameState),
+}
+
+impl AzeSubscriptions {
+    /// Subscribes to a table whose subscribers were sent `state` as their snapshot.
+    ///
+    /// The first subscriber sets the state the next diff is computed from, so no change made after
+    /// its snapshot is missed. Later ones keep it, at worst they get an event already in theirs.
+    pub fn subscribe(
+        &self,
+        game_id: AccountId,
+        hash: Digest,
+        state: GameState
+    ) -> broadcast::Receiver<GameEvent> {
+        self.channels
+            .write()
+            .unwrap()
+            .entry(game_id)
+            .or_insert_with(|| GameChannel {
+                sender: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+                last_seen: (hash, state),
+            })
+            .sender
+            .subscribe()
+    }
+
+    /// Returns the games somebody is listening to, dropping the channels left without receivers.
+    pub fn games(&self) -> Vec<AccountId> {
+        let mut channels = self.channels.write().unwrap();
+        channels.retain(|_, channel| channel.sender.receiver_count() > 0);
+        channels.keys().copied().collect()
+    }
+
+    /// Broadcasts what changed at a table since the state it was last seen in, if its hash moved.
+    pub fn publish(&self, game_id: AccountId, hash: Digest, state: GameState) {
+        let mut channels = self.channels.write().unwrap();
+        let Some(channel) = channels.get_mut(&game_id) else {
+            return;
+        };
+        let (last_hash, last_state) = &channel.last_seen;
+        if *last_hash == hash {
+            return;
+        }
+
+        for event in diff_game_state(last_state, &state) {
+            // sending only fails once every receiver is gone
+            let _ = channel.sender.send(event);
+        }
+        channel.last_seen = (hash, state);
+    }
+}
+
+pub type SharedAzeSubscriptions = Data<AzeSubscriptions>;
+
+/// Players waiting for a table at each stake level, and the tables matchmaking seated them at.
+///
+/// Every change is written to the repository before it is applied here, so the lobby is found as it
+/// was when the server restarts.
+pub struct AzeLobby {
+    // players a queue waits for before a table is opened for them
+    table_seats: u8,
+    repository: SharedAzeRepository,
+    queues: RwLock<BTreeMap<StakeLevel, Vec<AccountId>>>,
+    seated: RwLock<BTreeMap<AccountId, AccountId>>,
+}
+
+impl AzeLobby {
+    pub fn open(table_seats: u8, repository: SharedAzeRepository) -> rusqlite::Result<Self> {
+        Ok(Self {
+            table_seats,
+            queues: RwLock::new(repository.lobby_queues()?),
+            seated: RwLock::new(repository.lobby_seated()?),
+            repository,
+        })
+    }
+
+    pub fn table_seats(&self) -> u8 {
+        self.table_seats
+    }
+
+    /// Puts a player at the end of the queue of `stakes`, and takes a table worth of players out of
+    /// it once enough are waiting, none until then. Returns `None` if the player is already queued.
+    pub fn join(&self, stakes: StakeLevel, player_id: AccountId) -> rusqlite::Result<Option<Vec<AccountId>>> {
+        let mut queues = self.queues.write().unwrap();
+        if queues.values().any(|queue| queue.contains(&player_id)) {
+            return Ok(None);
+        }
+
+        let mut queue = queues.get(&stakes).cloned().unwrap_or_default();
+        queue.push(player_id);
+        let players = if queue.len() < (self.table_seats as usize) {
+            vec![]
+        } else {
+            queue.drain(..self.table_seats as usize).collect()
+        };
+
+        self.repository.save_lobby_queue(stakes, &queue)?;
+        self.repository.unseat_lobby_player(player_id)?;
+        self.seated.write().unwrap().remove(&player_id);
+        if queue.is_empty() {
+            queues.remove(&stakes);
+        } else {
+            queues.insert(stakes, queue);
+        }
+        Ok(Some(players))
+    }
+
+    /// Puts players back at the front of their queue, in the same order, when their table could not be opened.
+    pub fn requeue(&self, stakes: StakeLevel, player_ids: Vec<AccountId>) -> rusqlite::Result<()> {
+        let mut queues = self.queues.write().unwrap();
+        let mut queue = player_ids;
+        queue.extend(queues.get(&stakes).into_iter().flatten());
+
+        self.repository.save_lobby_queue(stakes, &queue)?;
+        queues.insert(stakes, queue);
+        Ok(())
+    }
+
+    pub fn leave(&self, player_id: AccountId) -> rusqlite::Result<bool> {
+        let mut queues = self.queues.write().unwrap();
+        let Some((stakes, queue)) = queues.iter().find(|(_, queue)| queue.contains(&player_id)) else {
+            return Ok(false);
+        };
+        let stakes = *stakes;
+        let queue: Vec<AccountId> = queue
+            .iter()
+            .copied()
+            .filter(|id| *id != player_id)
+            .collect();
+
+        self.repository.save_lobby_queue(stakes, &queue)?;
+        // a queue left empty is dropped
+        if queue.is_empty() {
+            queues.remove(&stakes);
+        } else {
+            queues.insert(stakes, queue);
+        }
+        Ok(true)
+    }
+
+    pub fn seat(&self, player_ids: &[AccountId], game_id: AccountId) -> rusqlite::Result<()> {
+        let mut seated = self.seated.write().unwrap();
+        self.repository.seat_lobby_players(player_ids, game_id)?;
+        for player_id in player_ids {
+            seated.insert(*player_id, game_id);
+        }
+        Ok(())
+    }
+
+    pub fn status(&self, player_id: AccountId) -> Option<QueueStatus> {
+        let queues = self.queues.read().unwrap();
+        for (stakes, queue) in queues.iter() {
+            if let Some(position) = queue.iter().position(|id| *id == player_id) {
+                return Some(QueueStatus::Waiting {
+                    stakes: *stakes,
+                    position: (position + 1) as u8,
+                    waiting: queue.len() as u8,
+                });
+            }
+        }
+        self.seated
+            .read()
+            .unwrap()
+            .get(&player_id)
+            .map(|game_id| QueueStatus::Seated { game_id: (*game_id).into() })
+    }
+
+    /// Number of players waiting at each stake level that has a queue.
+    pub fn queues(&self) -> Vec<(StakeLevel, u8)> {
+        self.queues
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(stakes, queue)| (*stakes, queue.len() as u8))
+            .collect()
+    }
+}
+
+pub type SharedAzeLobby = Data<AzeLobby>;
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    const GAME_ID: u64 = 0x8000_0000_0000_0100;
+
+    // ids of off-chain regular accounts, the high bits carry the storage and account type
+    fn account_id(id: u64) -> AccountId {
+        AccountId::try_from(0x8000_0000_0000_0000 | id).unwrap()
+    }
+
+    fn stakes(small_blind: u8) -> StakeLevel {
+        StakeLevel { faucet_id: 0x8000_0000_0000_0300, small_blind, buy_in: 100 }
+    }
+
+    fn lobby(table_seats: u8) -> AzeLobby {
+        let repository = AzeRepository::open(Path::new(":memory:")).unwrap();
+        AzeLobby::open(table_seats, Data::new(repository)).unwrap()
+    }
+
+    fn waiting(stakes: StakeLevel, position: u8, waiting: u8) -> Option<QueueStatus> {
+        Some(QueueStatus::Waiting { stakes, position, waiting })
+    }
+
+    #[test]
+    fn test_queue_opens_a_table_once_full() {
+        let lobby = lobby(3);
+
+        assert_eq!(lobby.join(stakes(5), account_id(1)).unwrap(), Some(vec![]));
+        assert_eq!(lobby.join(stakes(5), account_id(2)).unwrap(), Some(vec![]));
+        // players are only matched at the same stakes
+        assert_eq!(lobby.join(stakes(10), account_id(3)).unwrap(), Some(vec![]));
+        // nor queued twice, at any stakes
+        assert_eq!(lobby.join(stakes(10), account_id(1)).unwrap(), None);
+        assert_eq!(lobby.queues(), vec![(stakes(5), 2), (stakes(10), 1)]);
+
+        assert_eq!(
+            lobby.join(stakes(5), account_id(4)).unwrap(),
+            Some(vec![account_id(1), account_id(2), account_id(4)])
+        );
+        assert_eq!(lobby.queues(), vec![(stakes(10), 1)]);
+    }
+
+    #[test]
+    fn test_requeued_players_are_back_in_front() {
+        let lobby = lobby(2);
+
+        lobby.join(stakes(5), account_id(1)).unwrap();
+        let players = lobby.join(stakes(5), account_id(2)).unwrap().unwrap();
+        assert_eq!(players, vec![account_id(1), account_id(2)]);
+        assert_eq!(lobby.join(stakes(5), account_id(3)).unwrap(), Some(vec![]));
+
+        // the table could not be opened, the players seated for it go back ahead of those who came later
+        lobby.requeue(stakes(5), players).unwrap();
+        assert_eq!(lobby.status(account_id(1)), waiting(stakes(5), 1, 3));
+        assert_eq!(lobby.status(account_id(2)), waiting(stakes(5), 2, 3));
+        assert_eq!(lobby.status(account_id(3)), waiting(stakes(5), 3, 3));
+    }
+
+    #[test]
+    fn test_players_leave_their_queue() {
+        let lobby = lobby(3);
+        lobby.join(stakes(5), account_id(1)).unwrap();
+        lobby.join(stakes(5), account_id(2)).unwrap();
+        lobby.join(stakes(10), account_id(3)).unwrap();
+
+        assert!(lobby.leave(account_id(1)).unwrap());
+        assert!(!lobby.leave(account_id(1)).unwrap());
+        assert_eq!(lobby.status(account_id(1)), None);
+        assert_eq!(lobby.status(account_id(2)), waiting(stakes(5), 1, 1));
+
+        // a queue left empty is dropped
+        assert!(lobby.leave(account_id(3)).unwrap());
+        assert_eq!(lobby.queues(), vec![(stakes(5), 1)]);
+    }
+
+    #[test]
+    fn test_status_follows_the_player_from_queue_to_table() {
+        let lobby = lobby(2);
+        assert_eq!(lobby.status(account_id(1)), None);
+
+        lobby.join(stakes(5), account_id(1)).unwrap();
+        assert_eq!(lobby.status(account_id(1)), waiting(stakes(5), 1, 1));
+
+        let players = lobby.join(stakes(5), account_id(2)).unwrap().unwrap();
+        lobby.seat(&players, account_id(GAME_ID)).unwrap();
+        assert_eq!(lobby.status(account_id(1)), Some(QueueStatus::Seated { game_id: GAME_ID }));
+        assert_eq!(lobby.status(account_id(2)), Some(QueueStatus::Seated { game_id: GAME_ID }));
+
+        // joining a queue again leaves the table behind
+        lobby.join(stakes(10), account_id(1)).unwrap();
+        assert_eq!(lobby.status(account_id(1)), waiting(stakes(10), 1, 1));
+    }
+
+    #[test]
+    fn test_lobby_is_restored_from_the_repository() {
+        let repository = Data::new(AzeRepository::open(Path::new(":memory:")).unwrap());
+        let lobby = AzeLobby::open(2, repository.clone()).unwrap();
+        lobby.join(stakes(5), account_id(1)).unwrap();
+        lobby.join(stakes(10), account_id(2)).unwrap();
+        lobby.join(stakes(5), account_id(3)).unwrap();
+        lobby.seat(&[account_id(1), account_id(3)], account_id(GAME_ID)).unwrap();
+        lobby.join(stakes(5), account_id(4)).unwrap();
+
+        // a server restarted on the same repository finds the queues and seats as they were left
+        let lobby = AzeLobby::open(2, repository).unwrap();
+        assert_eq!(lobby.queues(), vec![(stakes(5), 1), (stakes(10), 1)]);
+        assert_eq!(lobby.status(account_id(4)), waiting(stakes(5), 1, 1));
+        assert_eq!(lobby.status(account_id(2)), waiting(stakes(10), 1, 1));
+        assert_eq!(lobby.status(account_id(3)), Some(QueueStatus::Seated { game_id: GAME_ID }));
+    }
+}
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use actix_web::rt::time::sleep;
+use aze_lib::client::AzeClient;
+use aze_lib::storage::read_game_state;
+use aze_lib::transport::fetch_consumed_notes;
+use crate::repository::AzeRepository;
+use crate::state::{ SharedAzeClient, SharedAzeRepository, SharedAzeSubscriptions };
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Background task pushing table updates to subscribers and emptying the inbox.
+///
+/// Every interval the shared client is synced with the node, and each game account with
+/// subscribers whose hash moved since they were last sent an update gets its state diff broadcast.
+/// Inbox notes are dropped once their nullifier shows up on chain.
+pub async fn sync_game_updates(
+    client: SharedAzeClient,
+    repository: SharedAzeRepository,
+    subscriptions: SharedAzeSubscriptions
+) {
+    // the inbox is checked from genesis after a restart, notes left in it may have been consumed meanwhile
+    let mut inbox_block_num = 0;
+
+    loop {
+        sleep(SYNC_INTERVAL).await;
+
+        let mut client = client.lock().await;
+        inbox_block_num = prune_inbox(&mut client, &repository, inbox_block_num).await;
+
+        let game_ids = subscriptions.games();
+        if game_ids.is_empty() {
+            continue;
+        }
+
+        if let Err(err) = client.sync_state().await {
+            log::warn!("Failed to sync state: {err}");
+            continue;
+        }
+
+        for game_id in game_ids {
+            let Ok((game_account, _)) = client.get_account(game_id) else {
+                continue;
+            };
+            subscriptions.publish(game_id, game_account.hash(), read_game_state(game_account.storage()));
+        }
+    }
+}
+
+// removes the inbox notes consumed after `block_num` and returns the block the inbox is checked up to
+async fn prune_inbox(client: &mut AzeClient, repository: &AzeRepository, block_num: u32) -> u32 {
+    let notes = match repository.inbox_notes() {
+        Ok(notes) if !notes.is_empty() => notes,
+        Ok(_) => {
+            return block_num;
+        }
+        Err(err) => {
+            log::error!("Failed to read the inbox: {err}");
+            return block_num;
+        }
+    };
+
+    match fetch_consumed_notes(client, &notes, block_num).await {
+        Ok((consumed_note_ids, synced_block_num)) => {
+            if let Err(err) = repository.remove_inbox_notes(&consumed_note_ids) {
+                log::error!("Failed to empty the inbox: {err}");
+                return block_num;
+            }
+            synced_block_num
+        }
+        Err(err) => {
+            log::warn!("Failed to check inbox notes: {err}");
+            block_num
+        }
+    }
+}
@@ -1,5 +1,9 @@
 mod utils;
+// the integration cases, run here against the live node rather than the in-memory chain
+#[path = "../integration/cases.rs"]
+mod cases;
 use aze_lib::client::{
+    create_aze_client,
     AzeClient,
     AzeGameMethods,
     AzeAccountTemplate,
@@ -26,7 +30,7 @@ use miden_objects::{
 
 #[tokio::test]
 async fn test_e2e() {
-    let mut client: AzeClient = utils::create_test_client();
+    let mut client: AzeClient = create_aze_client();
 
     let (game_account, player1_account_id, faucet_account_id, game_slot_data) = utils::setup_accounts(
         &mut client
@@ -224,4 +228,39 @@ async fn test_e2e() {
     );
 
     println!("----->>> Showdown");
+}
+
+#[tokio::test]
+async fn test_create_aze_game_account() {
+    cases::create_aze_game_account(&mut create_aze_client()).await;
+}
+
+#[tokio::test]
+async fn test_cards_distribution() {
+    cases::cards_distribution(&mut create_aze_client()).await;
+}
+
+#[tokio::test]
+async fn test_play_bet() {
+    cases::play_bet(&mut create_aze_client()).await;
+}
+
+#[tokio::test]
+async fn test_play_raise() {
+    cases::play_raise(&mut create_aze_client()).await;
+}
+
+#[tokio::test]
+async fn test_play_call() {
+    cases::play_call(&mut create_aze_client()).await;
+}
+
+#[tokio::test]
+async fn test_play_fold() {
+    cases::play_fold(&mut create_aze_client()).await;
+}
+
+#[tokio::test]
+async fn test_play_check() {
+    cases::play_check(&mut create_aze_client()).await;
 }
\ No newline at end of file
@@ -4,11 +4,6 @@ use aze_lib::client::{
     AzeAccountTemplate,
     AzeTransactionTemplate,
     SendCardTransactionData,
-    PlayBetTransactionData,
-    PlayRaiseTransactionData,
-    PlayCallTransactionData,
-    PlayFoldTransactionData,
-    PlayCheckTransactionData,
 };
 use aze_lib::constants::{
     BUY_IN_AMOUNT,
@@ -29,18 +24,13 @@ use aze_lib::constants::{
     PLAYER_CARD2_SLOT
 };
 use aze_lib::executor::execute_tx_and_sync;
-use aze_lib::utils::{ get_random_coin, load_config };
 use aze_lib::notes::{ consume_notes, mint_note };
 use aze_lib::storage::GameStorageSlotData;
-use miden_client::{
-    client::{
-        accounts::{ AccountTemplate, AccountStorageMode },
-        transactions::transaction_request::TransactionTemplate,
-        rpc::TonicRpcClient,
-    },
-    config::{ ClientConfig, RpcConfig },
-    errors::{ ClientError, NodeRpcClientError },
-    store::sqlite_store::SqliteStore,
+use aze_lib::table::play_action;
+use aze_types::actions::PokerAction;
+use miden_client::client::{
+    accounts::{ AccountTemplate, AccountStorageMode },
+    transactions::transaction_request::TransactionTemplate,
 };
 use miden_crypto::hash::rpo::RpoDigest;
 use miden_crypto::FieldElement;
@@ -51,23 +41,6 @@ use miden_objects::{
     notes::NoteType,
 };
 
-pub fn create_test_client() -> AzeClient {
-    let mut current_dir = std::env
-        ::current_dir()
-        .map_err(|err| err.to_string())
-        .unwrap();
-    current_dir.push("miden-client.toml");
-    let client_config = load_config(current_dir.as_path()).unwrap();
-
-    println!("Client Config: {:?}", client_config);
-
-    let rpc_endpoint = client_config.rpc.endpoint.to_string();
-    let store = SqliteStore::new((&client_config).into()).unwrap();
-    let executor_store = SqliteStore::new((&client_config).into()).unwrap();
-    let rng = get_random_coin();
-    AzeClient::new(TonicRpcClient::new(&rpc_endpoint), rng, store, executor_store, true)
-}
-
 pub fn setup_accounts(
     client: &mut AzeClient
 ) -> (Account, AccountId, AccountId, GameStorageSlotData) {
@@ -135,25 +108,15 @@ pub async fn bet(
 
     let player_index: u8 = FIRST_PLAYER_INDEX + PLAYER_STATS_SLOTS * (player_no - 1);
 
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-
-    let playbet_txn_data = PlayBetTransactionData::new(
-        Asset::Fungible(fungible_asset),
-        player_account_id,
+    play_action(
+        client,
         game_account_id,
-        player_bet
-    );
-
-    let transaction_template = AzeTransactionTemplate::PlayBet(playbet_txn_data);
-    let txn_request = client.build_aze_play_bet_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(client, txn_request.clone()).await;
-
-    let note_id = txn_request.expected_output_notes()[0].id();
-    let note = client.get_input_note(note_id).unwrap();
-
-    let tx_template = TransactionTemplate::ConsumeNotes(game_account_id, vec![note.id()]);
-    let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(client, tx_request).await;
+        player_account_id,
+        player_no - 1,
+        faucet_account_id,
+        PokerAction::Bet,
+        Some(player_bet)
+    ).await.unwrap();
 
     // update the game account storage
     let game_account = client.get_account(game_account_id).unwrap().0;
@@ -191,24 +154,15 @@ pub async fn check(
 
     let player_index: u8 = FIRST_PLAYER_INDEX + PLAYER_STATS_SLOTS * (player_no - 1);
 
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-
-    let playcheck_txn_data = PlayCheckTransactionData::new(
-        Asset::Fungible(fungible_asset),
+    play_action(
+        client,
+        game_account_id,
         player_account_id,
-        game_account_id
-    );
-
-    let transaction_template = AzeTransactionTemplate::PlayCheck(playcheck_txn_data);
-    let txn_request = client.build_aze_play_check_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(client, txn_request.clone()).await;
-
-    let note_id = txn_request.expected_output_notes()[0].id();
-    let note = client.get_input_note(note_id).unwrap();
-
-    let tx_template = TransactionTemplate::ConsumeNotes(game_account_id, vec![note.id()]);
-    let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(client, tx_request).await;
+        player_no - 1,
+        faucet_account_id,
+        PokerAction::Check,
+        None
+    ).await.unwrap();
 
     // check next turn
     assert_next_turn(&client, game_account_id, player_index, last_raiser, last_phase_digest).await;
@@ -232,24 +186,15 @@ pub async fn fold(
     let player_index: u8 = FIRST_PLAYER_INDEX + PLAYER_STATS_SLOTS * (player_no - 1);
     let fold_index = player_index + IS_FOLD_OFFSET;
 
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-
-    let playfold_txn_data = PlayFoldTransactionData::new(
-        Asset::Fungible(fungible_asset),
+    play_action(
+        client,
+        game_account_id,
         player_account_id,
-        game_account_id
-    );
-
-    let transaction_template = AzeTransactionTemplate::PlayFold(playfold_txn_data);
-    let txn_request = client.build_aze_play_fold_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(client, txn_request.clone()).await;
-
-    let note_id = txn_request.expected_output_notes()[0].id();
-    let note = client.get_input_note(note_id).unwrap();
-
-    let tx_template = TransactionTemplate::ConsumeNotes(game_account_id, vec![note.id()]);
-    let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(client, tx_request).await;
+        player_no - 1,
+        faucet_account_id,
+        PokerAction::Fold,
+        None
+    ).await.unwrap();
 
     // update the game account storage
     let game_account = client.get_account(game_account_id).unwrap().0;
@@ -282,24 +227,15 @@ pub async fn call(
 
     let player_index: u8 = FIRST_PLAYER_INDEX + PLAYER_STATS_SLOTS * (player_no - 1);
 
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-
-    let playcall_txn_data = PlayCallTransactionData::new(
-        Asset::Fungible(fungible_asset),
+    play_action(
+        client,
+        game_account_id,
         player_account_id,
-        game_account_id
-    );
-
-    let transaction_template = AzeTransactionTemplate::PlayCall(playcall_txn_data);
-    let txn_request = client.build_aze_play_call_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(client, txn_request.clone()).await;
-
-    let note_id = txn_request.expected_output_notes()[0].id();
-    let note = client.get_input_note(note_id).unwrap();
-
-    let tx_template = TransactionTemplate::ConsumeNotes(game_account_id, vec![note.id()]);
-    let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(client, tx_request).await;
+        player_no - 1,
+        faucet_account_id,
+        PokerAction::Call,
+        None
+    ).await.unwrap();
 
     // check next turn
     assert_next_turn(&client, game_account_id, player_index, last_raiser, last_phase_digest).await;
@@ -321,25 +257,15 @@ pub async fn raise(
     let last_raiser = game_account_storage.get_item(RAISER_INDEX_SLOT);
     let last_phase_digest = game_account_storage.get_item(CURRENT_PHASE_SLOT);
 
-    let fungible_asset = FungibleAsset::new(faucet_account_id, BUY_IN_AMOUNT).unwrap();
-
-    let playraise_txn_data = PlayRaiseTransactionData::new(
-        Asset::Fungible(fungible_asset),
-        player_account_id,
+    play_action(
+        client,
         game_account_id,
-        player_bet
-    );
-
-    let transaction_template = AzeTransactionTemplate::PlayRaise(playraise_txn_data);
-    let txn_request = client.build_aze_play_raise_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(client, txn_request.clone()).await;
-
-    let note_id = txn_request.expected_output_notes()[0].id();
-    let note = client.get_input_note(note_id).unwrap();
-
-    let tx_template = TransactionTemplate::ConsumeNotes(game_account_id, vec![note.id()]);
-    let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(client, tx_request).await;
+        player_account_id,
+        player_no - 1,
+        faucet_account_id,
+        PokerAction::Raise,
+        Some(player_bet)
+    ).await.unwrap();
 
     // update the game account storage
     let game_account = client.get_account(game_account_id).unwrap().0;
@@ -410,14 +336,14 @@ pub async fn deal_card(
     let transaction_template = AzeTransactionTemplate::SendCard(sendcard_txn_data);
 
     let txn_request = client.build_aze_send_card_tx_request(transaction_template).unwrap();
-    execute_tx_and_sync(client, txn_request.clone()).await;
+    execute_tx_and_sync(client, txn_request.clone()).await.unwrap();
 
     let note_id = txn_request.expected_output_notes()[0].id();
     let note = client.get_input_note(note_id).unwrap();
 
     let tx_template = TransactionTemplate::ConsumeNotes(player_account_id, vec![note.id()]);
     let tx_request = client.build_transaction_request(tx_template).unwrap();
-    execute_tx_and_sync(client, tx_request).await;
+    execute_tx_and_sync(client, tx_request).await.unwrap();
     // check player cards
     let (account, _) = client.get_account(player_account_id).unwrap();
     assert_eq!(
@@ -0,0 +1,110 @@
+use aze_lib::auth::{
+    is_request_fresh,
+    request_digest,
+    sign_request,
+    sign_table_consent,
+    table_consent_digest,
+    verify_request_signature,
+    NonceRegistry,
+    MAX_REQUEST_AGE_SECS,
+};
+use aze_lib::rng::AzeRng;
+use miden_objects::{ accounts::AccountId, crypto::dsa::rpo_falcon512::SecretKey, Word };
+
+const NOW: u64 = 1_700_000_000;
+const BODY: &[u8] = br#"{"player_id":1,"action":"check"}"#;
+const PATH: &str = "/v1/game/1/actions";
+
+// ids of off-chain regular accounts, the high bits carry the storage and account type
+fn account_id(id: u64) -> AccountId {
+    AccountId::try_from(id).unwrap()
+}
+
+#[test]
+fn test_signed_request_verifies_against_its_digest() {
+    let key = SecretKey::with_rng(&mut AzeRng);
+    let pub_key: Word = key.public_key().into();
+    let signature = sign_request(&key, "POST", PATH, NOW, 7, BODY);
+
+    assert!(verify_request_signature(pub_key, request_digest("POST", PATH, NOW, 7, BODY), &signature));
+    // the method is case insensitive
+    assert!(verify_request_signature(pub_key, request_digest("post", PATH, NOW, 7, BODY), &signature));
+
+    // every part of the request is covered by the signature
+    let tampered = [
+        request_digest("PUT", PATH, NOW, 7, BODY),
+        request_digest("POST", "/v1/game/2/actions", NOW, 7, BODY),
+        request_digest("POST", PATH, NOW + 1, 7, BODY),
+        request_digest("POST", PATH, NOW, 8, BODY),
+        request_digest("POST", PATH, NOW, 7, br#"{"player_id":1,"action":"fold"}"#),
+    ];
+    for digest in tampered {
+        assert!(!verify_request_signature(pub_key, digest, &signature));
+    }
+}
+
+#[test]
+fn test_signature_of_another_key_is_rejected() {
+    let key = SecretKey::with_rng(&mut AzeRng);
+    let other_key = SecretKey::with_rng(&mut AzeRng);
+    let digest = request_digest("POST", PATH, NOW, 7, BODY);
+
+    let signature = sign_request(&other_key, "POST", PATH, NOW, 7, BODY);
+    assert!(!verify_request_signature(key.public_key().into(), digest, &signature));
+
+    // garbage in the header is rejected rather than panicking
+    assert!(!verify_request_signature(key.public_key().into(), digest, "not hex"));
+    assert!(!verify_request_signature(key.public_key().into(), digest, "00ff"));
+}
+
+#[test]
+fn test_table_consent_covers_the_terms_of_the_table() {
+    let key = SecretKey::with_rng(&mut AzeRng);
+    let pub_key: Word = key.public_key().into();
+    let players = [0x8000_0000_0000_0001, 0x8000_0000_0000_0002];
+    let faucet = 0x8000_0000_0000_0300;
+    let signature = sign_table_consent(&key, &players, faucet, 5, 100, NOW, 7);
+
+    assert!(verify_request_signature(pub_key, table_consent_digest(&players, faucet, 5, 100, NOW, 7), &signature));
+
+    let tampered = [
+        table_consent_digest(&[players[1], players[0]], faucet, 5, 100, NOW, 7),
+        table_consent_digest(&players[..1], faucet, 5, 100, NOW, 7),
+        table_consent_digest(&players, faucet + 1, 5, 100, NOW, 7),
+        table_consent_digest(&players, faucet, 10, 100, NOW, 7),
+        table_consent_digest(&players, faucet, 5, 200, NOW, 7),
+        table_consent_digest(&players, faucet, 5, 100, NOW + 1, 7),
+        table_consent_digest(&players, faucet, 5, 100, NOW, 8),
+    ];
+    for digest in tampered {
+        assert!(!verify_request_signature(pub_key, digest, &signature));
+    }
+}
+
+#[test]
+fn test_expired_timestamps_are_rejected() {
+    assert!(is_request_fresh(NOW, NOW));
+    assert!(is_request_fresh(NOW - MAX_REQUEST_AGE_SECS, NOW));
+    assert!(!is_request_fresh(NOW - MAX_REQUEST_AGE_SECS - 1, NOW));
+    // clocks drift both ways
+    assert!(is_request_fresh(NOW + MAX_REQUEST_AGE_SECS, NOW));
+    assert!(!is_request_fresh(NOW + MAX_REQUEST_AGE_SECS + 1, NOW));
+}
+
+#[test]
+fn test_nonces_are_accepted_once_per_account() {
+    let nonces = NonceRegistry::default();
+    let player = account_id(0x8000_0000_0000_0001);
+    let other_player = account_id(0x8000_0000_0000_0002);
+
+    assert!(nonces.register(player, 7, NOW, NOW));
+    // replaying the same signed request
+    assert!(!nonces.register(player, 7, NOW, NOW + 10));
+    assert!(nonces.register(player, 8, NOW, NOW + 10));
+    assert!(nonces.register(other_player, 7, NOW, NOW + 10));
+
+    // once the request expired the nonce is dropped, its timestamp is what rejects a replay then
+    let later = NOW + MAX_REQUEST_AGE_SECS + 1;
+    assert!(nonces.register(player, 9, later, later));
+    assert!(nonces.register(player, 7, later, later));
+}
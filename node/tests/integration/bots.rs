@@ -0,0 +1,180 @@
+use aze_lib::bots::{
+    hand_strength,
+    is_hand_over,
+    run_bots,
+    AlwaysCall,
+    Bot,
+    BotAction,
+    RandomStrategy,
+    Strategy,
+    TightAggressive,
+};
+use aze_lib::client::{ AzeAccountTemplate, AzeGameMethods };
+use aze_lib::constants::{
+    BUY_IN_AMOUNT,
+    FIRST_PLAYER_INDEX,
+    HIGHEST_BET,
+    NO_OF_PLAYERS,
+    PLAYER_INITIAL_BALANCE,
+    SMALL_BLIND_AMOUNT,
+};
+use aze_lib::executor::execute_tx_and_sync;
+use aze_lib::mock::create_mock_aze_client;
+use aze_lib::storage::GameStorageSlotData;
+use aze_lib::table::deal_hole_cards;
+use aze_types::actions::{ Card, GameState, PlayerState };
+use miden_client::client::{
+    accounts::{ AccountStorageMode, AccountTemplate },
+    transactions::transaction_request::TransactionTemplate,
+};
+use miden_objects::assets::TokenSymbol;
+
+fn card(suit: u8, rank: u8) -> Card {
+    Card { suit, rank }
+}
+
+// pre-flop table where seat 0 faces a raise to 10
+fn facing_raise() -> GameState {
+    GameState {
+        small_blind: SMALL_BLIND_AMOUNT,
+        big_blind: SMALL_BLIND_AMOUNT * 2,
+        buy_in: BUY_IN_AMOUNT as u8,
+        player_count: NO_OF_PLAYERS,
+        current_turn: 0,
+        raiser: Some(3),
+        highest_bet: 10,
+        current_phase: 0,
+        check_count: 0,
+        players: (0..NO_OF_PLAYERS)
+            .map(|seat| PlayerState {
+                seat,
+                bet: if seat == 3 { 10 } else { 0 },
+                balance: PLAYER_INITIAL_BALANCE,
+                has_folded: false,
+            })
+            .collect(),
+        community_cards: vec![],
+    }
+}
+
+#[test]
+fn test_hand_strength() {
+    // hole cards, community cards, lowest and highest expected strength
+    let cases: [(&str, [Card; 2], Vec<Card>, f64, f64); 6] = [
+        ("seven deuce offsuit", [card(1, 7), card(2, 2)], vec![], 0.0, 0.3),
+        ("pocket aces", [card(1, 1), card(2, 1)], vec![], 1.0, 1.0),
+        ("high card on the flop", [card(1, 7), card(2, 2)], vec![card(3, 9), card(4, 11), card(1, 13)], 0.0, 0.35),
+        ("two pair on the flop", [card(1, 9), card(2, 11)], vec![card(3, 9), card(4, 11), card(1, 2)], 0.7, 1.0),
+        ("wheel straight", [card(1, 1), card(2, 2)], vec![card(3, 3), card(4, 4), card(1, 5)], 1.0, 1.0),
+        ("flush", [card(2, 2), card(2, 9)], vec![card(2, 4), card(2, 11), card(2, 13), card(1, 13)], 1.0, 1.0),
+    ];
+
+    for (name, hole_cards, community_cards, lowest, highest) in cases {
+        let strength = hand_strength(&hole_cards, &community_cards);
+        assert!(lowest <= strength && strength <= highest, "{name}: strength {strength}");
+    }
+}
+
+#[test]
+fn test_strategies_facing_a_raise() {
+    let game_state = facing_raise();
+    let weak = [card(1, 7), card(2, 2)];
+    let aces = [card(1, 1), card(2, 1)];
+
+    assert_eq!(AlwaysCall.decide(&game_state, 0, &weak), BotAction::call());
+    assert_eq!(TightAggressive::default().decide(&game_state, 0, &weak), BotAction::fold());
+    assert_eq!(
+        TightAggressive::default().decide(&game_state, 0, &aces),
+        BotAction::raise(10 + SMALL_BLIND_AMOUNT * 2)
+    );
+
+    // the same seed plays the same actions
+    let first: Vec<BotAction> = {
+        let mut random = RandomStrategy::new(7);
+        (0..10).map(|_| random.decide(&game_state, 0, &weak)).collect()
+    };
+    let mut random = RandomStrategy::new(7);
+    for action in first {
+        assert_eq!(random.decide(&game_state, 0, &weak), action);
+    }
+}
+
+#[tokio::test]
+async fn test_bots_play_a_hand_to_the_end() {
+    let mut client = create_mock_aze_client();
+
+    let slot_data = GameStorageSlotData::new(
+        SMALL_BLIND_AMOUNT,
+        BUY_IN_AMOUNT as u8,
+        NO_OF_PLAYERS,
+        FIRST_PLAYER_INDEX,
+        HIGHEST_BET,
+        PLAYER_INITIAL_BALANCE
+    );
+    let (game_account, _) = client
+        .new_game_account(
+            AzeAccountTemplate::GameAccount {
+                mutable_code: false,
+                storage_mode: AccountStorageMode::Local,
+            },
+            Some(slot_data)
+        )
+        .unwrap();
+    let (faucet_account, _) = client
+        .new_account(AccountTemplate::FungibleFaucet {
+            token_symbol: TokenSymbol::new("MATIC").unwrap(),
+            decimals: 8,
+            max_supply: 1_000_000_000,
+            storage_mode: AccountStorageMode::Local,
+        })
+        .unwrap();
+
+    let mut player_account_ids = vec![];
+    for _ in 0..NO_OF_PLAYERS {
+        let (player_account, _) = client
+            .new_game_account(
+                AzeAccountTemplate::PlayerAccount {
+                    mutable_code: false,
+                    storage_mode: AccountStorageMode::Local,
+                },
+                None
+            )
+            .unwrap();
+        player_account_ids.push(player_account.id());
+    }
+
+    let deal_notes = deal_hole_cards(
+        &mut client,
+        game_account.id(),
+        &player_account_ids,
+        faucet_account.id(),
+        BUY_IN_AMOUNT
+    ).await.unwrap();
+    for (player_account_id, deal_note) in player_account_ids.iter().zip(deal_notes) {
+        let tx_template = TransactionTemplate::ConsumeNotes(*player_account_id, vec![deal_note.id()]);
+        let tx_request = client.build_transaction_request(tx_template).unwrap();
+        execute_tx_and_sync(&mut client, tx_request).await.unwrap();
+    }
+
+    let strategies: Vec<Box<dyn Strategy>> = vec![
+        Box::new(AlwaysCall),
+        Box::new(TightAggressive::default()),
+        Box::new(RandomStrategy::new(42)),
+        Box::new(AlwaysCall)
+    ];
+    let mut bots: Vec<Bot> = player_account_ids
+        .into_iter()
+        .zip(strategies)
+        .map(|(account_id, strategy)| Bot { account_id, strategy })
+        .collect();
+
+    let game_state = run_bots(
+        &mut client,
+        game_account.id(),
+        faucet_account.id(),
+        &mut bots,
+        100
+    ).await.unwrap();
+
+    assert!(is_hand_over(&game_state), "hand stopped at {:?}", game_state);
+}
@@ -0,0 +1,285 @@
+use aze_lib::accounts::create_basic_aze_player_account;
+use aze_lib::constants::{
+    BUY_IN_AMOUNT,
+    FIRST_PLAYER_INDEX,
+    GAME_STORAGE_VERSION,
+    HIGHEST_BET,
+    NO_OF_PLAYERS,
+    PLAYER_INITIAL_BALANCE,
+    SMALL_BLIND_AMOUNT,
+};
+use aze_lib::contracts::{ is_compatible_game_code, is_compatible_player_code };
+use aze_lib::engine::BettingRound;
+use aze_lib::harness::GameTestHarness;
+use aze_lib::rng::AzeRng;
+use aze_lib::storage::{ game_storage_version, migrate_game_storage, GameStorageSlotData };
+use aze_types::actions::{ GameState, PokerAction };
+use miden_lib::AuthScheme;
+use miden_objects::{
+    accounts::{ AccountStorageType, AccountType },
+    crypto::dsa::rpo_falcon512::SecretKey,
+    Word,
+};
+use proptest::prelude::*;
+
+fn slot_data() -> GameStorageSlotData {
+    GameStorageSlotData::new(
+        SMALL_BLIND_AMOUNT,
+        BUY_IN_AMOUNT as u8,
+        NO_OF_PLAYERS,
+        FIRST_PLAYER_INDEX,
+        HIGHEST_BET,
+        PLAYER_INITIAL_BALANCE
+    )
+}
+
+fn new_table() -> GameTestHarness {
+    GameTestHarness::new(slot_data())
+}
+
+// state of the table the checks of a case are run against, seat 0 is the one that acted
+struct Expected {
+    current_turn: u8,
+    highest_bet: u8,
+    raiser: Option<u8>,
+    check_count: u8,
+    bet: u8,
+    balance: u8,
+    has_folded: bool,
+}
+
+fn assert_table(name: &str, state: &GameState, expected: &Expected) {
+    let player = &state.players[0];
+    assert_eq!(state.current_turn, expected.current_turn, "{name}: current turn");
+    assert_eq!(state.highest_bet, expected.highest_bet, "{name}: highest bet");
+    assert_eq!(state.raiser, expected.raiser, "{name}: raiser");
+    assert_eq!(state.check_count, expected.check_count, "{name}: check count");
+    assert_eq!(player.bet, expected.bet, "{name}: player bet");
+    assert_eq!(player.balance, expected.balance, "{name}: player balance");
+    assert_eq!(player.has_folded, expected.has_folded, "{name}: player folded");
+}
+
+#[test]
+fn test_first_action_of_a_hand() {
+    let raise = SMALL_BLIND_AMOUNT * 2;
+    let cases = [
+        (
+            "raise",
+            PokerAction::Raise,
+            raise,
+            Expected {
+                current_turn: 1,
+                highest_bet: raise,
+                raiser: Some(0),
+                check_count: 0,
+                bet: raise,
+                balance: PLAYER_INITIAL_BALANCE - raise,
+                has_folded: false,
+            },
+        ),
+        (
+            "call",
+            PokerAction::Call,
+            HIGHEST_BET,
+            Expected {
+                current_turn: 1,
+                highest_bet: HIGHEST_BET,
+                raiser: None,
+                check_count: 0,
+                bet: HIGHEST_BET,
+                balance: PLAYER_INITIAL_BALANCE - HIGHEST_BET,
+                has_folded: false,
+            },
+        ),
+        (
+            "fold",
+            PokerAction::Fold,
+            0,
+            Expected {
+                current_turn: 1,
+                highest_bet: HIGHEST_BET,
+                raiser: None,
+                check_count: 0,
+                bet: 0,
+                balance: PLAYER_INITIAL_BALANCE,
+                has_folded: true,
+            },
+        ),
+        (
+            "check",
+            PokerAction::Check,
+            0,
+            Expected {
+                current_turn: 1,
+                highest_bet: HIGHEST_BET,
+                raiser: None,
+                check_count: 1,
+                bet: 0,
+                balance: PLAYER_INITIAL_BALANCE,
+                has_folded: false,
+            },
+        ),
+    ];
+
+    for (name, action, chips, expected) in cases {
+        let mut table = new_table();
+        let state = table.play(action, chips).unwrap();
+        assert_table(name, &state, &expected);
+    }
+}
+
+#[test]
+fn test_update_current_turn() {
+    // seats folded before the turn starts moving, and the seats the turn then lands on in order
+    let cases: [(&str, usize, &[u8]); 3] = [
+        ("nobody folded", 0, &[1, 2, 3, 0, 1]),
+        ("first seat folded", 1, &[2, 3, 1, 2]),
+        ("two seats folded", 2, &[3, 2, 3]),
+    ];
+
+    for (name, folds, turns) in cases {
+        let mut table = new_table();
+        for _ in 0..folds {
+            table.play(PokerAction::Fold, 0).unwrap();
+        }
+
+        for expected_turn in turns {
+            let state = table.update_current_turn().unwrap();
+            assert_eq!(state.current_turn, *expected_turn, "{name}");
+        }
+    }
+}
+
+#[test]
+fn test_turn_stays_with_the_last_player_in_the_hand() {
+    let mut table = new_table();
+    for _ in 1..NO_OF_PLAYERS {
+        table.play(PokerAction::Fold, 0).unwrap();
+    }
+    let last_seat = NO_OF_PLAYERS - 1;
+    assert_eq!(table.game_state().current_turn, last_seat);
+
+    // every other seat is checked once and the turn comes back round to the same player
+    let state = table.update_current_turn().unwrap();
+    assert_eq!(state.current_turn, last_seat);
+
+    // nobody would be left to act after the last fold
+    assert!(table.play(PokerAction::Fold, 0).is_err());
+    assert_eq!(table.game_state().current_turn, last_seat);
+}
+
+#[test]
+fn test_raise_above_balance_is_rejected() {
+    let mut table = new_table();
+
+    assert!(table.play(PokerAction::Raise, PLAYER_INITIAL_BALANCE + 1).is_err());
+    // a failed transaction leaves the table as it was
+    assert_eq!(table.game_state().current_turn, 0);
+}
+
+#[test]
+fn test_accounts_run_the_contracts_of_this_build() {
+    let table = new_table();
+    let game_account = table.game_account();
+
+    let key = SecretKey::with_rng(&mut AzeRng);
+    let (player_account, _) = create_basic_aze_player_account(
+        [7; 32],
+        AuthScheme::RpoFalcon512 { pub_key: key.public_key() },
+        AccountType::RegularAccountImmutableCode,
+        AccountStorageType::OffChain
+    ).unwrap();
+
+    assert!(is_compatible_game_code(game_account.code()));
+    assert!(is_compatible_player_code(player_account.code()));
+    // neither contract exports every procedure of the other
+    assert!(!is_compatible_game_code(player_account.code()));
+    assert!(!is_compatible_player_code(game_account.code()));
+
+    assert_eq!(game_storage_version(game_account.storage()), GAME_STORAGE_VERSION);
+}
+
+#[test]
+fn test_upgrade_migrates_a_v1_game_account() {
+    let mut table = GameTestHarness::with_updatable_code(slot_data());
+    table.play(PokerAction::Call, HIGHEST_BET).unwrap();
+    let state_before = table.game_state();
+
+    let storage = table.game_account().storage();
+    assert_eq!(game_storage_version(storage), 1);
+    // version 1 is the layout of this build, only the code changes
+    assert!(migrate_game_storage(storage).is_empty());
+
+    let code_root = table.game_account().code().root();
+    let state = table.upgrade(code_root).unwrap();
+    assert_eq!(state, state_before);
+    assert_eq!(game_storage_version(table.game_account().storage()), GAME_STORAGE_VERSION);
+
+    // the table goes on from where it was
+    let state = table.play(PokerAction::Fold, 0).unwrap();
+    assert_eq!(state.current_turn, 2);
+}
+
+#[test]
+fn test_immutable_game_account_cannot_upgrade() {
+    let mut table = new_table();
+    let code_root = table.game_account().code().root();
+
+    assert!(table.upgrade(code_root).is_err());
+}
+
+#[test]
+fn test_rotated_game_key_signs_the_next_actions() {
+    let mut table = new_table();
+    let new_key = SecretKey::with_rng(&mut AzeRng);
+    let new_pub_key: Word = new_key.public_key().into();
+
+    table.rotate_auth_key(new_key).unwrap();
+    let auth_key: Word = table.game_account().storage().get_item(0).into();
+    assert_eq!(auth_key, new_pub_key);
+
+    // the harness signs with the new key from now on, the account takes it
+    let state = table.play(PokerAction::Call, HIGHEST_BET).unwrap();
+    assert_eq!(state.current_turn, 1);
+}
+
+fn poker_action() -> impl Strategy<Value = (PokerAction, u8)> {
+    (
+        prop_oneof![
+            Just(PokerAction::Bet),
+            Just(PokerAction::Raise),
+            Just(PokerAction::Call),
+            Just(PokerAction::Fold),
+            Just(PokerAction::Check)
+        ],
+        0..=PLAYER_INITIAL_BALANCE,
+    )
+}
+
+proptest! {
+    // every case executes a transaction per action, so only a few sequences are played per run
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn test_betting_matches_reference(actions in prop::collection::vec(poker_action(), 1..12)) {
+        let mut table = new_table();
+        let mut reference = BettingRound::from(&table.game_state());
+
+        for (action, amount) in actions {
+            // actions the reference refuses are left out, the sequence goes on without them
+            let mut expected = reference.clone();
+            if expected.apply(action, amount).is_err() {
+                continue;
+            }
+            let chips = match action {
+                PokerAction::Bet | PokerAction::Raise => amount,
+                PokerAction::Call => reference.call_amount().unwrap(),
+                PokerAction::Fold | PokerAction::Check => 0,
+            };
+
+            let state = table.play(action, chips).unwrap();
+            prop_assert_eq!(BettingRound::from(&state), expected.clone(), "after {:?} {}", action, amount);
+            reference = expected;
+        }
+    }
+}
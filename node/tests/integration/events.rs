@@ -0,0 +1,83 @@
+use aze_lib::constants::{ NO_OF_PLAYERS, PLAYER_INITIAL_BALANCE, SMALL_BLIND_AMOUNT };
+use aze_lib::storage::diff_game_state;
+use aze_types::actions::{ Card, GameState, PlayerState };
+use aze_types::events::GameEvent;
+
+// pre-flop table with seat 0 to act and nothing bet yet
+fn new_table() -> GameState {
+    GameState {
+        small_blind: SMALL_BLIND_AMOUNT,
+        big_blind: SMALL_BLIND_AMOUNT * 2,
+        buy_in: PLAYER_INITIAL_BALANCE,
+        player_count: NO_OF_PLAYERS,
+        current_turn: 0,
+        raiser: None,
+        highest_bet: 0,
+        current_phase: 0,
+        check_count: 0,
+        players: (0..NO_OF_PLAYERS)
+            .map(|seat| PlayerState {
+                seat,
+                bet: 0,
+                balance: PLAYER_INITIAL_BALANCE,
+                has_folded: false,
+            })
+            .collect(),
+        community_cards: vec![],
+    }
+}
+
+#[test]
+fn test_diff_of_unchanged_table_is_empty() {
+    let table = new_table();
+    assert_eq!(diff_game_state(&table, &table), vec![]);
+}
+
+#[test]
+fn test_diff_of_bet() {
+    let old = new_table();
+    let mut new = old.clone();
+    new.players[0].bet = 10;
+    new.players[0].balance = PLAYER_INITIAL_BALANCE - 10;
+    new.highest_bet = 10;
+    new.current_turn = 1;
+
+    assert_eq!(diff_game_state(&old, &new), vec![
+        GameEvent::BetPlaced { seat: 0, bet: 10, balance: PLAYER_INITIAL_BALANCE - 10 },
+        GameEvent::TurnChanged { seat: 1 },
+    ]);
+}
+
+#[test]
+fn test_diff_of_fold() {
+    let old = new_table();
+    let mut new = old.clone();
+    new.players[0].has_folded = true;
+    new.current_turn = 1;
+
+    assert_eq!(diff_game_state(&old, &new), vec![
+        GameEvent::PlayerFolded { seat: 0 },
+        GameEvent::TurnChanged { seat: 1 },
+    ]);
+
+    // a seat that already folded is not reported again
+    assert_eq!(diff_game_state(&new, &new), vec![]);
+}
+
+#[test]
+fn test_diff_of_phase_change_reveals_only_new_cards() {
+    let flop = vec![Card { suit: 1, rank: 2 }, Card { suit: 2, rank: 9 }, Card { suit: 3, rank: 13 }];
+    let turn = Card { suit: 4, rank: 1 };
+
+    let mut old = new_table();
+    old.current_phase = 1;
+    old.community_cards = flop.clone();
+    let mut new = old.clone();
+    new.current_phase = 2;
+    new.community_cards.push(turn);
+
+    assert_eq!(diff_game_state(&old, &new), vec![
+        GameEvent::PhaseChanged { phase: 2 },
+        GameEvent::CardsRevealed { cards: vec![turn] },
+    ]);
+}
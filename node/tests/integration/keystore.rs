@@ -0,0 +1,110 @@
+use std::{ env::temp_dir, fs, path::PathBuf };
+
+use aze_lib::keystore::{ AzeKeyStore, KeyStoreError };
+use aze_lib::rng::AzeRng;
+use miden_objects::{
+    accounts::AccountId,
+    crypto::dsa::rpo_falcon512::SecretKey,
+    utils::Serializable,
+};
+
+const PASSPHRASE: &str = "correct horse battery staple";
+const EXPORT_PASSPHRASE: &str = "handed to the player";
+
+// ids of off-chain regular accounts, the high bits carry the storage and account type
+fn account_id(id: u64) -> AccountId {
+    AccountId::try_from(id).unwrap()
+}
+
+// a fresh directory per test, tests of the same binary run concurrently
+fn keys_dir(test_name: &str) -> PathBuf {
+    let dir = temp_dir().join(format!("aze-keystore-{test_name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_stored_key_is_sealed_and_opened() {
+    let dir = keys_dir("seal");
+    let keystore = AzeKeyStore::open(&dir, PASSPHRASE).unwrap();
+    let player = account_id(0x8000_0000_0000_0001);
+    let key = SecretKey::with_rng(&mut AzeRng);
+
+    keystore.insert_key(player, &key).unwrap();
+    assert_eq!(keystore.get_key(player).unwrap().to_bytes(), key.to_bytes());
+
+    // the key never reaches the disk in the clear
+    let key_file = fs::read(dir.join(format!("{player}.key"))).unwrap();
+    let key_bytes = key.to_bytes();
+    assert!(!key_file.windows(key_bytes.len()).any(|window| window == key_bytes.as_slice()));
+
+    // a store opened later on reads what the previous one wrote
+    let reopened = AzeKeyStore::open(&dir, PASSPHRASE).unwrap();
+    assert_eq!(reopened.get_key(player).unwrap().to_bytes(), key.to_bytes());
+
+    let unknown = account_id(0x8000_0000_0000_0002);
+    assert!(matches!(keystore.get_key(unknown), Err(KeyStoreError::KeyNotFound(id)) if id == unknown));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_exported_key_is_imported_into_another_store() {
+    let dir = keys_dir("export");
+    let other_dir = keys_dir("import");
+    let keystore = AzeKeyStore::open(&dir, PASSPHRASE).unwrap();
+    let other_keystore = AzeKeyStore::open(&other_dir, "another passphrase").unwrap();
+    let player = account_id(0x8000_0000_0000_0001);
+    let key = SecretKey::with_rng(&mut AzeRng);
+
+    keystore.insert_key(player, &key).unwrap();
+    let key_file = keystore.export_key(player, EXPORT_PASSPHRASE).unwrap();
+
+    assert_eq!(other_keystore.import_key(&key_file, EXPORT_PASSPHRASE).unwrap(), player);
+    assert_eq!(other_keystore.get_key(player).unwrap().to_bytes(), key.to_bytes());
+
+    fs::remove_dir_all(dir).unwrap();
+    fs::remove_dir_all(other_dir).unwrap();
+}
+
+#[test]
+fn test_wrong_passphrase_is_rejected() {
+    let dir = keys_dir("passphrase");
+    let keystore = AzeKeyStore::open(&dir, PASSPHRASE).unwrap();
+    let player = account_id(0x8000_0000_0000_0001);
+
+    keystore.insert_key(player, &SecretKey::with_rng(&mut AzeRng)).unwrap();
+
+    let wrong_keystore = AzeKeyStore::open(&dir, "wrong passphrase").unwrap();
+    assert!(matches!(wrong_keystore.get_key(player), Err(KeyStoreError::DecryptionFailed)));
+
+    let key_file = keystore.export_key(player, EXPORT_PASSPHRASE).unwrap();
+    assert!(matches!(keystore.import_key(&key_file, PASSPHRASE), Err(KeyStoreError::DecryptionFailed)));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_tampered_key_files_are_rejected() {
+    let dir = keys_dir("tampered");
+    let keystore = AzeKeyStore::open(&dir, PASSPHRASE).unwrap();
+    let player = account_id(0x8000_0000_0000_0001);
+    let other_player = account_id(0x8000_0000_0000_0002);
+
+    keystore.insert_key(player, &SecretKey::with_rng(&mut AzeRng)).unwrap();
+    let key_file = keystore.export_key(player, EXPORT_PASSPHRASE).unwrap();
+
+    let mut flipped = key_file.clone();
+    let last = flipped.len() - 1;
+    flipped[last] ^= 1;
+    assert!(matches!(keystore.import_key(&flipped, EXPORT_PASSPHRASE), Err(KeyStoreError::DecryptionFailed)));
+    assert!(
+        matches!(keystore.import_key(&key_file[..8], EXPORT_PASSPHRASE), Err(KeyStoreError::MalformedKeyFile))
+    );
+
+    // a file copied over another account's one does not sign for it
+    fs::copy(dir.join(format!("{player}.key")), dir.join(format!("{other_player}.key"))).unwrap();
+    assert!(matches!(keystore.get_key(other_player), Err(KeyStoreError::MalformedKeyFile)));
+
+    fs::remove_dir_all(dir).unwrap();
+}
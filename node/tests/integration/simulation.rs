@@ -0,0 +1,24 @@
+use aze_lib::bots::{ AlwaysCall, RandomStrategy, Strategy, TightAggressive };
+use aze_lib::simulator::simulate_game;
+
+const MAX_ACTIONS: usize = 100;
+
+fn strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(AlwaysCall),
+        Box::new(TightAggressive::default()),
+        Box::new(RandomStrategy::new(7)),
+        Box::new(AlwaysCall)
+    ]
+}
+
+#[tokio::test]
+async fn test_simulation_replays_from_its_seed() {
+    let record = simulate_game(2024, strategies(), MAX_ACTIONS).await.unwrap();
+    let replay = simulate_game(2024, strategies(), MAX_ACTIONS).await.unwrap();
+    assert_eq!(record, replay);
+
+    let other = simulate_game(2025, strategies(), MAX_ACTIONS).await.unwrap();
+    assert_ne!(record.game_account_id, other.game_account_id);
+    assert_ne!(record.deal_note_ids, other.deal_note_ids);
+}
@@ -0,0 +1,78 @@
+use aze_lib::accounts::create_basic_aze_game_account;
+use aze_lib::constants::{
+    BUY_IN_AMOUNT,
+    FIRST_DECK_SLOT,
+    FIRST_PLAYER_INDEX,
+    HIGHEST_BET,
+    LAST_DECK_SLOT,
+    NO_OF_PLAYERS,
+    PLAYER_INITIAL_BALANCE,
+    SMALL_BLIND_AMOUNT,
+};
+use aze_lib::rng::AzeRng;
+use aze_lib::storage::{ read_game_state, GameStorageSlotData };
+use aze_lib::transport::{ sign_game_snapshot, verify_game_snapshot };
+use miden_lib::AuthScheme;
+use miden_objects::{
+    accounts::{ Account, AccountStorageType, AccountType },
+    crypto::dsa::rpo_falcon512::SecretKey,
+    Digest,
+};
+use rand::Rng;
+
+fn new_game_account() -> (Account, SecretKey) {
+    let game_key = SecretKey::with_rng(&mut AzeRng);
+    let slot_data = GameStorageSlotData::new(
+        SMALL_BLIND_AMOUNT,
+        BUY_IN_AMOUNT as u8,
+        NO_OF_PLAYERS,
+        FIRST_PLAYER_INDEX,
+        HIGHEST_BET,
+        PLAYER_INITIAL_BALANCE
+    ).with_shuffle_seed(AzeRng.gen());
+
+    let (game_account, _) = create_basic_aze_game_account(
+        AzeRng.gen(),
+        AuthScheme::RpoFalcon512 { pub_key: game_key.public_key() },
+        AccountType::RegularAccountImmutableCode,
+        AccountStorageType::OffChain,
+        slot_data
+    ).unwrap();
+    (game_account, game_key)
+}
+
+#[test]
+fn test_snapshot_leaves_the_hidden_deck_out() {
+    let (game_account, game_key) = new_game_account();
+    let snapshot = sign_game_snapshot(&game_account, &game_key);
+
+    // no card is revealed before the flop
+    let deck_slots = FIRST_DECK_SLOT..=LAST_DECK_SLOT;
+    assert!(snapshot.slots.iter().all(|slot| !deck_slots.contains(&slot.index)));
+
+    let game_storage = verify_game_snapshot(&snapshot, game_account.hash()).unwrap();
+    assert_eq!(read_game_state(&game_storage), read_game_state(game_account.storage()));
+    for index in deck_slots {
+        assert_eq!(game_storage.get_item(index), Digest::default());
+    }
+}
+
+#[test]
+fn test_snapshot_not_matching_its_commitment_is_rejected() {
+    let (game_account, game_key) = new_game_account();
+    let snapshot = sign_game_snapshot(&game_account, &game_key);
+
+    let (other_account, _) = new_game_account();
+    assert!(verify_game_snapshot(&snapshot, other_account.hash()).is_none());
+
+    // a slot changed after signing
+    let mut tampered = snapshot.clone();
+    let slot = tampered.slots.last_mut().unwrap();
+    slot.value = Digest::default().to_hex();
+    assert!(verify_game_snapshot(&tampered, game_account.hash()).is_none());
+
+    // signed by a key other than the one in slot 0
+    let other_key = SecretKey::with_rng(&mut AzeRng);
+    let forged = sign_game_snapshot(&game_account, &other_key);
+    assert!(verify_game_snapshot(&forged, game_account.hash()).is_none());
+}
@@ -10,22 +10,117 @@ use actix_web::{
 };
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
+use crate::actions::Card;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AzeStorageMode {
+    // kept off-chain, the node only records a commitment to the game state
+    #[serde(alias = "off_chain")]
+    Local,
+    OnChain,
+}
 
 #[derive(Deserialize, Serialize)]
-pub struct AccountCreationResponse {
-    pub is_created: bool,
+pub struct GameCreationRequest {
+    // one seat per player, in seat order
+    pub player_ids: Vec<u64>,
+    pub small_blind: u8,
+    pub buy_in: u8,
+    pub faucet_id: u64,
+    pub storage_mode: AzeStorageMode,
+    // agreement of every player but the one opening the table, who signs the request itself
+    #[serde(default)]
+    pub consents: Vec<TableConsent>,
+}
+
+// a player's signature over the terms of a table, see `aze_lib::auth::table_consent_digest`
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TableConsent {
+    pub player_id: u64,
+    pub timestamp: u64,
+    pub nonce: u64,
+    // hex encoded Falcon-512 signature
+    pub signature: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct GameCreationResponse {
+    pub game_id: u64,
+    // hex encoded ids of the notes dealing the hole cards, in seating order
+    pub deal_note_ids: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PlayerAccountCreationRequest {
+    // hex encoded Falcon-512 public key controlling the account, the secret key never leaves the player
+    pub pub_key: String,
 }
 
 #[derive(Deserialize, Serialize)]
 pub struct PlayerAccountCreationResponse {
-    pub is_created: bool,
     pub account_id: u64,
+    // hex encoded seed the account id was derived from
+    pub account_seed: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct AssetBalance {
+    pub faucet_id: u64,
+    pub amount: u64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PlayerStateResponse {
+    pub account_id: u64,
+    pub balances: Vec<AssetBalance>,
+    pub hole_cards: Vec<Card>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TableSummary {
+    pub game_id: u64,
+    // player account ids in seat order
+    pub player_ids: Vec<u64>,
+    pub faucet_id: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct SnapshotSlot {
+    pub index: u8,
+    // hex encoded slot value
+    pub value: String,
+}
+
+/// State of a game account signed by its key, without the cards of the deck still hidden.
+///
+/// The roots hash to the account commitment recorded on chain along with its id and nonce. Empty
+/// slots and the hidden cards are left out, the latter are committed to by `deck_commitment`.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct GameSnapshot {
+    pub game_id: u64,
+    pub nonce: u64,
+    // hex encoded roots of the account
+    pub vault_root: String,
+    pub storage_root: String,
+    pub code_root: String,
+    // hex encoded hash of the hidden cards, in slot order
+    pub deck_commitment: String,
+    pub slots: Vec<SnapshotSlot>,
+    // hex encoded signature of the account commitment by the game key
+    pub signature: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct TablesResponse {
+    pub tables: Vec<TableSummary>,
 }
 
 #[derive(Debug, Display)]
 pub enum AccountCreationError {
     AccountCreationFailed,
     BadTaskRequest,
+    Unauthorized,
 }
 
 impl ResponseError for AccountCreationError {
@@ -39,6 +134,33 @@ impl ResponseError for AccountCreationError {
         match self {
             AccountCreationError::AccountCreationFailed => StatusCode::FAILED_DEPENDENCY,
             AccountCreationError::BadTaskRequest => StatusCode::BAD_REQUEST,
+            AccountCreationError::Unauthorized => StatusCode::UNAUTHORIZED,
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Display)]
+pub enum AccountQueryError {
+    AccountNotFound,
+    BadQueryRequest,
+    Unauthorized,
+    // the server could not read its own records
+    QueryFailed,
+}
+
+impl ResponseError for AccountQueryError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::json())
+            .body(self.to_string())
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AccountQueryError::AccountNotFound => StatusCode::NOT_FOUND,
+            AccountQueryError::BadQueryRequest => StatusCode::BAD_REQUEST,
+            AccountQueryError::Unauthorized => StatusCode::UNAUTHORIZED,
+            AccountQueryError::QueryFailed => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
@@ -16,11 +16,89 @@ use serde::{ Deserialize, Serialize };
 pub enum GameActionError {
     GameActionFailed,
     BadActionRequest,
+    Unauthorized,
 }
 
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PokerAction {
+    Bet,
+    Raise,
+    Call,
+    Fold,
+    Check,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct GameActionRequest {
+    pub player_id: u64,
+    pub action: PokerAction,
+    // only read for bet and raise
+    pub amount: Option<u8>,
+}
+
+// returned when the player proves the action on their own machine
 #[derive(Deserialize, Serialize)]
-pub struct GameActionResponse {
-    pub is_taken: bool,
+pub struct UnsignedTransactionResponse {
+    // hex encoded request, to be signed and proven by the player
+    pub transaction_request: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ProvenTransactionRequest {
+    pub player_id: u64,
+    // hex encoded proven transaction
+    pub proven_transaction: String,
+}
+
+// action played at a table, recorded by the server once the game account consumed its note
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ActionRecord {
+    pub hand_number: u32,
+    pub seat: u8,
+    pub player_id: u64,
+    pub action: PokerAction,
+    pub amount: Option<u8>,
+    // hex encoded id of the action note
+    pub note_id: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ActionsResponse {
+    // in the order they were played
+    pub actions: Vec<ActionRecord>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Card {
+    pub suit: u8,
+    pub rank: u8,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlayerState {
+    pub seat: u8,
+    pub bet: u8,
+    pub balance: u8,
+    pub has_folded: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct GameState {
+    pub small_blind: u8,
+    pub big_blind: u8,
+    pub buy_in: u8,
+    pub player_count: u8,
+    // seat of the player whose turn it is
+    pub current_turn: u8,
+    // seat of the last raiser in the current phase, if any
+    pub raiser: Option<u8>,
+    pub highest_bet: u8,
+    pub current_phase: u8,
+    pub check_count: u8,
+    pub players: Vec<PlayerState>,
+    // only the community cards revealed so far in the hand
+    pub community_cards: Vec<Card>,
 }
 
 impl ResponseError for GameActionError {
@@ -34,6 +112,7 @@ impl ResponseError for GameActionError {
         match self {
             GameActionError::GameActionFailed => StatusCode::FAILED_DEPENDENCY,
             GameActionError::BadActionRequest => StatusCode::BAD_REQUEST,
+            GameActionError::Unauthorized => StatusCode::UNAUTHORIZED,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,28 @@
+use serde::{ Deserialize, Serialize };
+use crate::actions::{ Card, GameState };
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    // full table state, sent once when a client subscribes
+    Snapshot {
+        state: GameState,
+    },
+    TurnChanged {
+        seat: u8,
+    },
+    BetPlaced {
+        seat: u8,
+        bet: u8,
+        balance: u8,
+    },
+    PlayerFolded {
+        seat: u8,
+    },
+    PhaseChanged {
+        phase: u8,
+    },
+    CardsRevealed {
+        cards: Vec<Card>,
+    },
+}
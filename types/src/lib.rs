@@ -1,3 +1,5 @@
 pub mod accounts;
 pub mod actions;
+pub mod events;
+pub mod lobby;
 pub mod notes;
\ No newline at end of file
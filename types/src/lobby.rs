@@ -0,0 +1,52 @@
+use serde::{ Deserialize, Serialize };
+
+// tables are only matched with players betting the same chips at the same blinds
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StakeLevel {
+    pub faucet_id: u64,
+    pub small_blind: u8,
+    pub buy_in: u8,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct LobbyTable {
+    pub game_id: u64,
+    pub stakes: StakeLevel,
+    // a table seats the players it was opened for, no one joins it later
+    pub player_count: u8,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct QueueSummary {
+    pub stakes: StakeLevel,
+    pub waiting: u8,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct LobbyResponse {
+    // tables whose hand is still being played, in game id order
+    pub tables: Vec<LobbyTable>,
+    // players needed to open a table from a queue
+    pub table_seats: u8,
+    pub queues: Vec<QueueSummary>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct QueueRequest {
+    pub player_id: u64,
+    pub stakes: StakeLevel,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum QueueStatus {
+    Waiting {
+        stakes: StakeLevel,
+        // 1 for the player first in line
+        position: u8,
+        waiting: u8,
+    },
+    Seated {
+        game_id: u64,
+    },
+}
@@ -0,0 +1,52 @@
+use actix_web::{ error::ResponseError, http::{ header::ContentType, StatusCode }, HttpResponse };
+use derive_more::Display;
+use serde::{ Deserialize, Serialize };
+
+#[derive(Deserialize, Serialize)]
+pub struct NoteSummary {
+    pub note_id: String,
+    pub sender_id: u64,
+    // pending notes are expected but not yet committed on chain
+    pub status: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct GameNotesResponse {
+    pub notes: Vec<NoteSummary>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PrivateNoteDelivery {
+    pub recipient_id: u64,
+    // hex encoded note
+    pub note: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct PrivateNotesResponse {
+    // hex encoded notes, handed out until they are consumed on chain
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Display)]
+pub enum NoteInboxError {
+    BadNoteRequest,
+    Unauthorized,
+    InboxFailed,
+}
+
+impl ResponseError for NoteInboxError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::json())
+            .body(self.to_string())
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            NoteInboxError::BadNoteRequest => StatusCode::BAD_REQUEST,
+            NoteInboxError::Unauthorized => StatusCode::UNAUTHORIZED,
+            NoteInboxError::InboxFailed => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}